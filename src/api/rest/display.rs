@@ -3,6 +3,7 @@ use crate::{api::tree::Tree, config::Config};
 use super::{Comment, DueDateFormatter, Label, Project, Section, Task};
 use chrono::Utc;
 use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
 
 /// FullComment allows to display full comment metadata when [std::fmt::Display]ing it.
 pub struct FullComment<'a>(pub &'a Comment);
@@ -160,3 +161,18 @@ impl std::fmt::Display for TableTask<'_> {
         Ok(())
     }
 }
+
+/// Used to serialize a [`Task`] together with the names of the items it references.
+#[derive(Serialize)]
+pub struct ResolvedTask<'a> {
+    /// The task itself.
+    #[serde(flatten)]
+    pub task: &'a Task,
+    /// Name of the [`Task::project_id`] project.
+    pub project_name: Option<&'a str>,
+    /// Name of the [`Task::section_id`] section.
+    pub section_name: Option<&'a str>,
+    /// Comments of the task, if they were requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<&'a [Comment]>,
+}
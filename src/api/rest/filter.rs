@@ -0,0 +1,106 @@
+use owo_colors::{OwoColorize, Stream};
+use serde::{Deserialize, Serialize};
+
+/// FilterID specifies the unique ID of a [`Filter`].
+pub type FilterID = String;
+
+/// Filter is a filter query saved under a name, so that it can be reused.
+///
+/// Taken from the [Developer Documentation](https://developer.todoist.com/api/v1/#tag/Sync/Filters).
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+pub struct Filter {
+    /// Unique ID of a filter.
+    pub id: FilterID,
+    /// Name of the filter.
+    pub name: String,
+    /// The filter query, as it would be used with [`super::Gateway::tasks`].
+    pub query: String,
+    /// The display color of the filter as given from the API.
+    pub color: String,
+    /// The order among filters if we were to sort them.
+    pub item_order: isize,
+    /// Toggle for marking a filter as a favorite.
+    pub is_favorite: bool,
+    /// Set for filters that have been deleted.
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+impl Ord for Filter {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match self.item_order.cmp(&other.item_order) {
+            core::cmp::Ordering::Equal => {}
+            ord => return ord,
+        }
+        self.id.cmp(&other.id)
+    }
+}
+
+impl PartialOrd for Filter {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for Filter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.name
+                .if_supports_color(Stream::Stdout, |text| text.bright_blue()),
+            self.query
+                .if_supports_color(Stream::Stdout, |text| text.yellow())
+        )
+    }
+}
+
+/// Command used with [`super::Gateway::create_filter`] to create a new [`Filter`].
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CreateFilter {
+    /// Name of the filter to create.
+    pub name: String,
+    /// The filter query.
+    pub query: String,
+    /// Color of the filter icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
+/// Command used with [`super::Gateway::update_filter`] to update a [`Filter`]. Only fields that
+/// are set are changed.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct UpdateFilter {
+    /// New name of the filter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// New filter query.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// New color of the filter icon.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Mark as favorite or not.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_favorite: Option<bool>,
+}
+
+#[cfg(test)]
+impl Filter {
+    /// This is initializer is used for tests, as in general the tool relies on the API and not
+    /// local state.
+    pub fn new(id: &str, name: &str, query: &str) -> Filter {
+        Filter {
+            id: id.to_string(),
+            name: name.to_string(),
+            query: query.to_string(),
+            color: "".to_string(),
+            item_order: 0,
+            is_favorite: false,
+            is_deleted: false,
+        }
+    }
+}
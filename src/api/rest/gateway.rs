@@ -1,6 +1,6 @@
-use std::time::Duration;
+use std::{collections::HashMap, time::Duration};
 
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use color_eyre::{
     Result,
     eyre::{WrapErr, eyre},
@@ -15,16 +15,54 @@ use uuid::Uuid;
 /// Wrapper for paginated list responses from the Todoist API v1.
 #[derive(Deserialize)]
 struct PaginatedResponse<T> {
+    /// Some endpoints, like the one for completed tasks, call the results `items` instead.
+    #[serde(alias = "items")]
     results: Vec<T>,
     next_cursor: Option<String>,
 }
 
+/// Response of the sync endpoint, which is used for resources that have no REST endpoints.
+#[derive(Deserialize)]
+struct SyncResponse {
+    #[serde(default)]
+    filters: Vec<Filter>,
+    /// The comments of all active tasks.
+    #[serde(default)]
+    notes: Vec<Comment>,
+    /// The result of each command by its UUID, which is either `"ok"` or an error object.
+    #[serde(default)]
+    sync_status: HashMap<String, serde_json::Value>,
+    /// Maps the temporary IDs of created resources to their actual IDs.
+    #[serde(default)]
+    temp_id_mapping: HashMap<String, String>,
+}
+
+/// A single command that changes a resource through the sync endpoint.
+#[derive(Serialize)]
+struct SyncCommand<'a, T: Serialize> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    uuid: String,
+    temp_id: String,
+    args: T,
+}
+
+/// Adds the ID of the resource to the arguments of a [`SyncCommand`].
+#[derive(Serialize)]
+struct WithID<'a, T: Serialize> {
+    id: &'a str,
+    #[serde(flatten)]
+    args: T,
+}
+
 use super::{
-    Comment, CreateComment, CreateLabel, CreateProject, CreateSection, CreateTask, Label, LabelID,
-    Project, ProjectID, Section, SectionID, Task, TaskDue, TaskID, UpdateTask,
+    Comment, CreateComment, CreateFilter, CreateLabel, CreateProject, CreateSection, CreateTask,
+    Filter, FilterID, Label, LabelID, MoveTask, Project, ProjectID, Section, SectionID, Task,
+    TaskDue, TaskID, UpdateFilter, UpdateTask,
 };
 
 /// Makes network calls to the Todoist API and returns structs that can then be worked with.
+#[derive(Clone)]
 pub struct Gateway {
     client: ClientWithMiddleware,
     token: String,
@@ -82,6 +120,30 @@ impl Gateway {
         }
     }
 
+    /// Returns the tasks that were completed between `since` and `until`. The Todoist API allows
+    /// at most 3 months between the two.
+    pub async fn completed_tasks(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<Task>> {
+        self.get_list(
+            "api/v1/tasks/completed/by_completion_date",
+            vec![
+                (
+                    "since".to_string(),
+                    since.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ),
+                (
+                    "until".to_string(),
+                    until.to_rfc3339_opts(SecondsFormat::Secs, true),
+                ),
+            ],
+        )
+        .await
+        .wrap_err("unable to get completed tasks")
+    }
+
     /// Closes a task.
     ///
     /// Equivalent to pushing the circle in the UI.
@@ -126,6 +188,14 @@ impl Gateway {
         Ok(())
     }
 
+    /// Moves a task to another project, section or parent task.
+    pub async fn move_task(&self, id: &TaskID, task: &MoveTask) -> Result<()> {
+        self.post_empty(&format!("api/v1/tasks/{id}/move"), &task)
+            .await
+            .wrap_err("unable to move task")?;
+        Ok(())
+    }
+
     /// Returns the list of Projects.
     pub async fn projects(&self) -> Result<Vec<Project>> {
         self.get_list("api/v1/projects", vec![])
@@ -167,6 +237,18 @@ impl Gateway {
         .wrap_err("unable to get comments")
     }
 
+    /// Returns the comments of all active tasks with a single request.
+    pub async fn comments(&self) -> Result<Vec<Comment>> {
+        Ok(self
+            .sync(&[
+                ("sync_token", "*".to_string()),
+                ("resource_types", r#"["notes"]"#.to_string()),
+            ])
+            .await
+            .wrap_err("unable to get comments")?
+            .notes)
+    }
+
     /// Creates a comment by calling the API.
     pub async fn create_comment(&self, comment: &CreateComment) -> Result<Comment> {
         self.post("api/v1/comments", comment)
@@ -247,6 +329,53 @@ impl Gateway {
             .wrap_err("unable to delete label")
     }
 
+    /// Returns the list of saved [`Filter`]s, in their order.
+    pub async fn filters(&self) -> Result<Vec<Filter>> {
+        let response = self
+            .sync(&[
+                ("sync_token", "*".to_string()),
+                ("resource_types", r#"["filters"]"#.to_string()),
+            ])
+            .await
+            .wrap_err("unable to get filters")?;
+        let mut filters = response
+            .filters
+            .into_iter()
+            .filter(|f| !f.is_deleted)
+            .collect::<Vec<_>>();
+        filters.sort();
+        Ok(filters)
+    }
+
+    /// Creates a filter by calling the Todoist API, returning the ID of the new filter.
+    pub async fn create_filter(&self, filter: &CreateFilter) -> Result<FilterID> {
+        let (response, temp_id) = self
+            .sync_command("filter_add", filter)
+            .await
+            .wrap_err("unable to create filter")?;
+        response
+            .temp_id_mapping
+            .get(&temp_id)
+            .cloned()
+            .ok_or_else(|| eyre!("unable to create filter"))
+    }
+
+    /// Updates a filter with the data as specified in [`UpdateFilter`].
+    pub async fn update_filter(&self, id: &FilterID, filter: &UpdateFilter) -> Result<()> {
+        self.sync_command("filter_update", WithID { id, args: filter })
+            .await
+            .wrap_err("unable to update filter")?;
+        Ok(())
+    }
+
+    /// Deletes a filter by calling the Todoist API.
+    pub async fn delete_filter(&self, id: &FilterID) -> Result<()> {
+        self.sync_command("filter_delete", WithID { id, args: () })
+            .await
+            .wrap_err("unable to delete filter")?;
+        Ok(())
+    }
+
     /// Makes a GET request to the Todoist API with an optional query.
     async fn get<'a, T: 'a + Serialize, R: DeserializeOwned>(
         &self,
@@ -324,6 +453,48 @@ impl Gateway {
         Ok(())
     }
 
+    /// Sends a form encoded POST request to the sync endpoint of the Todoist API.
+    async fn sync(&self, params: &[(&str, String)]) -> Result<SyncResponse> {
+        let body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(params)
+            .finish();
+        handle_req(
+            self.client
+                .post(self.url.join("api/v1/sync")?)
+                .bearer_auth(&self.token)
+                .body(body)
+                .header(
+                    reqwest::header::CONTENT_TYPE,
+                    "application/x-www-form-urlencoded",
+                ),
+        )
+        .await?
+        .ok_or_else(|| eyre!("Invalid response from API"))
+    }
+
+    /// Runs a single command through the sync endpoint, returning the response and the temporary
+    /// ID that was used for the command.
+    async fn sync_command<T: Serialize>(
+        &self,
+        kind: &str,
+        args: T,
+    ) -> Result<(SyncResponse, String)> {
+        let command = SyncCommand {
+            kind,
+            uuid: Uuid::new_v4().to_string(),
+            temp_id: Uuid::new_v4().to_string(),
+            args,
+        };
+        let response = self
+            .sync(&[("commands", serde_json::to_string(&[&command])?)])
+            .await?;
+        match response.sync_status.get(&command.uuid) {
+            Some(status) if status == "ok" => Ok((response, command.temp_id)),
+            Some(status) => Err(eyre!("Bad response from API: {}", status)),
+            None => Err(eyre!("Invalid response from API")),
+        }
+    }
+
     /// Same as [`Gateway::post`], but doesn't require content to be set for the POST request.
     async fn post_empty<T: Serialize>(&self, path: &str, content: &T) -> Result<()> {
         self.post::<_, Task>(path, content).await?;
@@ -356,7 +527,7 @@ mod test {
     use serde::Serialize;
     use wiremock::{
         Mock, MockServer, ResponseTemplate,
-        matchers::{bearer_token, method, path, query_param},
+        matchers::{bearer_token, body_string_contains, method, path, query_param},
     };
 
     use super::*;
@@ -729,6 +900,58 @@ mod test {
         assert_eq!(task_comments[0].content, "no");
     }
 
+    #[tokio::test]
+    async fn completed_tasks() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/tasks/completed/by_completion_date"))
+            .and(query_param("since", "2022-06-01T00:00:00Z"))
+            .and(query_param("until", "2022-08-26T00:00:00Z"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [create_task("123", "456", "done")],
+                "next_cursor": null,
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let tasks = gw
+            .completed_tasks(
+                "2022-06-01T00:00:00Z".parse().unwrap(),
+                "2022-08-26T00:00:00Z".parse().unwrap(),
+            )
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].content, "done");
+    }
+
+    #[tokio::test]
+    async fn all_comments() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .and(body_string_contains("resource_types=%5B%22notes%22%5D"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "notes": [create_comment(
+                    "1",
+                    ThreadID::Task {
+                        task_id: "123".to_string(),
+                    },
+                    "hello",
+                )],
+            })))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let comments = gw.comments().await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].item_id.as_deref(), Some("123"));
+    }
+
     #[tokio::test]
     async fn creates_label() {
         let mock_server = MockServer::start().await;
@@ -762,6 +985,83 @@ mod test {
         assert!(closed.is_ok());
     }
 
+    #[tokio::test]
+    async fn lists_filters() {
+        let mock_server = MockServer::start().await;
+        let deleted = Filter {
+            is_deleted: true,
+            ..Filter::new("3", "old", "all")
+        };
+        let later = Filter {
+            item_order: 2,
+            ..Filter::new("1", "later", "7 days")
+        };
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .and(body_string_contains("resource_types=%5B%22filters%22%5D"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "filters": [later, deleted, Filter::new("2", "focus", "today")],
+            })))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let filters = gw.filters().await.unwrap();
+        assert_eq!(
+            filters.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            vec!["focus", "later"]
+        );
+    }
+
+    /// Answers sync commands like the Todoist API would, mapping temporary IDs to `123`.
+    fn sync_ok(req: &wiremock::Request) -> ResponseTemplate {
+        let commands = url::form_urlencoded::parse(&req.body)
+            .find(|(k, _)| k == "commands")
+            .map(|(_, v)| serde_json::from_str::<Vec<serde_json::Value>>(&v).unwrap())
+            .unwrap();
+        let command = &commands[0];
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "sync_status": {command["uuid"].as_str().unwrap(): "ok"},
+            "temp_id_mapping": {command["temp_id"].as_str().unwrap(): "123"},
+        }))
+    }
+
+    #[tokio::test]
+    async fn creates_filter() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .and(body_string_contains("filter_add"))
+            .respond_with(sync_ok)
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        let id = gw
+            .create_filter(&CreateFilter {
+                name: "focus".to_string(),
+                query: "today & p1".to_string(),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        mock_server.verify().await;
+        assert_eq!(id, "123");
+    }
+
+    #[tokio::test]
+    async fn delete_filter_fails() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/sync"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sync_status": {},
+            })))
+            .mount(&mock_server)
+            .await;
+        let gw = gateway("", &mock_server);
+        assert!(gw.delete_filter(&"123".to_string()).await.is_err());
+    }
+
     #[tokio::test]
     async fn creates_project() {
         let mock_server = MockServer::start().await;
@@ -8,6 +8,7 @@
 //! To get started, take a look at [`Gateway`].
 mod comment;
 mod display;
+mod filter;
 mod gateway;
 mod label;
 mod project;
@@ -16,6 +17,7 @@ mod task;
 
 pub use comment::*;
 pub use display::*;
+pub use filter::*;
 pub use gateway::*;
 pub use label::*;
 pub use project::*;
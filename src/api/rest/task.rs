@@ -143,25 +143,39 @@ pub enum Priority {
     Urgent = 4,
 }
 
+impl Priority {
+    /// Returns the priority as it's shown in the Todoist UI, like `p1` for [`Priority::Urgent`],
+    /// without any colors.
+    pub fn label(&self) -> &'static str {
+        // The priority display is reversed as in the actual desktop client compared to the API.
+        match self {
+            Priority::Normal => "p4",
+            Priority::High => "p3",
+            Priority::VeryHigh => "p2",
+            Priority::Urgent => "p1",
+        }
+    }
+}
+
 impl Display for Priority {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // The priority display is reversed as in the actual desktop client compared to the API.
+        let label = self.label();
         match self {
-            Priority::Normal => write!(f, "p4"),
+            Priority::Normal => write!(f, "{label}"),
             Priority::High => write!(
                 f,
                 "{}",
-                "p3".if_supports_color(Stream::Stdout, |text| text.blue())
+                label.if_supports_color(Stream::Stdout, |text| text.blue())
             ),
             Priority::VeryHigh => write!(
                 f,
                 "{}",
-                "p2".if_supports_color(Stream::Stdout, |text| text.yellow())
+                label.if_supports_color(Stream::Stdout, |text| text.yellow())
             ),
             Priority::Urgent => write!(
                 f,
                 "{}",
-                "p1".if_supports_color(Stream::Stdout, |text| text.red())
+                label.if_supports_color(Stream::Stdout, |text| text.red())
             ),
         }
     }
@@ -208,51 +222,60 @@ pub struct DueDate {
 /// too late or too soon.
 pub struct DueDateFormatter<'a>(pub &'a DueDate, pub &'a DateTime<Utc>);
 
+impl DueDateFormatter<'_> {
+    /// Returns whether the due date has already passed.
+    pub fn overdue(&self) -> bool {
+        match &self.0.exact {
+            Some(exact) => exact.datetime < *self.1,
+            None => self.0.date < self.1.date_naive(),
+        }
+    }
+
+    /// Returns the marker shown in front of recurring due dates, and the date itself, without
+    /// coloring the date.
+    fn parts(&self) -> (String, String) {
+        let marker = if self.0.is_recurring {
+            "[REPEAT] "
+                .if_supports_color(Stream::Stdout, |_| "🔁 ")
+                .to_string()
+        } else {
+            String::new()
+        };
+        let date = match &self.0.exact {
+            Some(exact) => exact.to_string(),
+            None => self.0.string.clone(),
+        };
+        (marker, date)
+    }
+
+    /// Returns the due date as it's shown, but without any coloring.
+    pub fn text(&self) -> String {
+        let (marker, date) = self.parts();
+        marker + &date
+    }
+}
+
 impl Display for DueDateFormatter<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if self.0.is_recurring {
+        let (marker, date) = self.parts();
+        if self.overdue() {
             write!(
                 f,
-                "{}",
-                "[REPEAT] ".if_supports_color(Stream::Stdout, |_| "🔁 ")
-            )?;
-        }
-        if let Some(exact) = &self.0.exact {
-            if exact.datetime >= *self.1 {
-                write!(
-                    f,
-                    "{}",
-                    exact.if_supports_color(Stream::Stdout, |text| text.bright_green())
-                )
-            } else {
-                write!(
-                    f,
-                    "{}",
-                    exact.if_supports_color(Stream::Stdout, |text| text.bright_red())
-                )
-            }
-        } else if self.0.date >= self.1.date_naive() {
-            write!(
-                f,
-                "{}",
-                self.0
-                    .string
-                    .if_supports_color(Stream::Stdout, |text| text.bright_green())
+                "{marker}{}",
+                date.if_supports_color(Stream::Stdout, |text| text.bright_red())
             )
         } else {
             write!(
                 f,
-                "{}",
-                self.0
-                    .string
-                    .if_supports_color(Stream::Stdout, |text| text.bright_red())
+                "{marker}{}",
+                date.if_supports_color(Stream::Stdout, |text| text.bright_green())
             )
         }
     }
 }
 
 /// Human representation of the due date.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskDue {
     /// Human readable representation of the date.
     #[serde(rename = "due_string")]
@@ -265,7 +288,7 @@ pub enum TaskDue {
     DateTime(DateTime<Utc>),
 }
 /// Command used with [`super::Gateway::create`] to create a new Task.
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
 pub struct CreateTask {
     /// Sets the [`Task::content`] on the new [`Task`].
     pub content: String,
@@ -292,10 +315,61 @@ pub struct CreateTask {
     pub assignee: Option<UserID>,
 }
 
+impl CreateTask {
+    /// Builds the [`Task`] that this command is going to create, as far as it's known without
+    /// asking the API. Human-readable due strings can't be interpreted locally, so they are kept
+    /// as they are and set to be due on the day of `now`.
+    pub fn preview(&self, now: &DateTime<Utc>) -> Task {
+        let due = self.due.as_ref().map(|due| match due {
+            TaskDue::String(string) => DueDate {
+                string: string.clone(),
+                date: now.date_naive(),
+                is_recurring: string.to_lowercase().starts_with("every"),
+                exact: None,
+            },
+            TaskDue::Date(date) => DueDate {
+                string: date.clone(),
+                date: date.parse().unwrap_or_else(|_| now.date_naive()),
+                is_recurring: false,
+                exact: None,
+            },
+            TaskDue::DateTime(datetime) => DueDate {
+                string: datetime.to_rfc3339(),
+                date: datetime.date_naive(),
+                is_recurring: false,
+                exact: Some(ExactTime {
+                    datetime: datetime.fixed_offset(),
+                    timezone: "UTC".to_string(),
+                }),
+            },
+        });
+        Task {
+            id: "new".to_string(),
+            project_id: self.project_id.clone().unwrap_or_default(),
+            section_id: self.section_id.clone(),
+            content: self.content.clone(),
+            description: self.description.clone().unwrap_or_default(),
+            checked: false,
+            labels: self.labels.clone(),
+            parent_id: self.parent_id.clone(),
+            child_order: self.order.unwrap_or_default(),
+            priority: self.priority.unwrap_or_default(),
+            due,
+            url: default_task_url(),
+            note_count: 0,
+            user_id: String::new(),
+            added_by_uid: None,
+            responsible_uid: self.assignee.clone(),
+            assigned_by_uid: None,
+            added_at: *now,
+        }
+    }
+}
+
 /// Command used with [`super::Gateway::update`] to update a [`Task`].
 ///
 /// Each field is optional, so if something exists, that part of the [`Task`] will get overwritten.
-#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct UpdateTask {
     /// Overwrites [`Task::content`] if set.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -320,6 +394,22 @@ pub struct UpdateTask {
     pub assignee: Option<UserID>,
 }
 
+/// Command used with [`super::Gateway::move_task`] to move a [`Task`] somewhere else.
+///
+/// Only one of the fields should be set, the task is moved to the end of that location.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct MoveTask {
+    /// Moves the task to the top level of this project.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<ProjectID>,
+    /// Moves the task into this section.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<SectionID>,
+    /// Moves the task under this parent task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<TaskID>,
+}
+
 #[cfg(test)]
 impl Task {
     /// This is initializer is used for tests, as in general the tool relies on the API and not
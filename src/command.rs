@@ -1,12 +1,17 @@
 use std::path::PathBuf;
 
 use crate::{
+    completions,
     config::Config,
-    labels, projects, sections,
-    tasks::{add, close, comment, create, edit, list, view},
+    export::{self, interchange, outline},
+    filters, labels, oauth,
+    output::Output,
+    projects, sections,
+    tasks::{add, agenda, close, comment, create, edit, list, search, view},
+    tui,
 };
 use clap::{Args, Parser, Subcommand};
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 
 /// Args are the main entry point struct of the CLI app.
 #[derive(Parser, Debug)]
@@ -15,6 +20,16 @@ pub struct Arguments {
     /// Overrides the config directory location.
     #[arg(long = "config_prefix")]
     config_prefix: Option<PathBuf>,
+    /// Sets the format that command results are written in. Machine-readable formats disable
+    /// interactive selection in lists.
+    #[arg(
+        value_enum,
+        short = 'o',
+        long = "output",
+        global = true,
+        default_value_t
+    )]
+    output: Output,
 
     #[command(subcommand)]
     command: Option<Commands>,
@@ -30,8 +45,21 @@ enum Commands {
         /// The Todoist API token.
         /// This can be taken from the Todoist client by going into
         /// Settings -> Integrations -> API token
-        token: String,
+        #[arg(required_unless_present = "oauth")]
+        token: Option<String>,
+        /// Authenticates through the Todoist OAuth flow in the browser instead of using an API
+        /// token.
+        #[arg(long = "oauth", conflicts_with = "token")]
+        oauth: bool,
+        #[command(flatten)]
+        oauth_params: oauth::Params,
     },
+    /// Prints a script that sets up completions for the shell, including the names of projects,
+    /// sections and labels. Add `source <(doist completions bash)` to your shell configuration.
+    Completions(completions::Params),
+    /// Prints the completions for the given command line. Used by the completion scripts.
+    #[command(name = "__complete", hide = true)]
+    Complete(completions::CompleteParams),
     /// Authenticated commands are commands that require a token to be set up via the Auth command
     /// before executing.
     #[command(flatten)]
@@ -42,7 +70,7 @@ enum Commands {
 enum AuthCommands {
     /// Adds a task.
     #[command(visible_alias = "a")]
-    Add(add::Params),
+    Add(Box<add::Params>),
     /// Creates a task interactively.
     #[command(visible_alias = "A")]
     Create(create::Params),
@@ -55,12 +83,18 @@ enum AuthCommands {
     /// Closes a task.
     #[command(visible_alias = "c")]
     Close(close::Params),
+    /// Shows tasks due in the next days, grouped by day.
+    #[command(visible_alias = "ag")]
+    Agenda(agenda::Params),
     /// View details of a single task.
     #[command(visible_alias = "v")]
     View(view::Params),
     /// Add a comment on a task.
     #[command(visible_alias = "C")]
     Comment(comment::Params),
+    /// Searches tasks by their content, description and comments.
+    #[command(visible_alias = "s")]
+    Search(search::Params),
 
     /// Manages projects.
     #[command(visible_alias = "p")]
@@ -68,6 +102,15 @@ enum AuthCommands {
     /// Manages labels.
     #[command(visible_alias = "lbl")]
     Labels(LabelArgs),
+    /// Manages saved filters and checks filter queries.
+    #[command(visible_alias = "filter")]
+    Filters(FilterArgs),
+    /// Exports tasks into other formats.
+    Export(ExportArgs),
+    /// Imports tasks from other todo list tools.
+    Import(ImportArgs),
+    /// Opens a full-screen terminal UI to browse and work with tasks.
+    Tui(tui::Params),
 }
 
 #[derive(Args, Debug)]
@@ -124,6 +167,68 @@ enum LabelCommands {
     Delete(labels::delete::Params),
 }
 
+#[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
+struct FilterArgs {
+    #[command(subcommand)]
+    command: Option<FilterCommands>,
+    #[command(flatten)]
+    params: filters::list::Params,
+}
+
+#[derive(Subcommand, Debug)]
+enum FilterCommands {
+    /// Lists all saved filters.
+    #[command(visible_alias = "l")]
+    List(filters::list::Params),
+    /// Adds (saves) a new filter.
+    #[command(visible_alias = "a")]
+    Add(filters::add::Params),
+    /// Edits the name or query of a saved filter.
+    #[command(visible_alias = "e")]
+    Edit(filters::edit::Params),
+    /// Deletes a saved filter.
+    #[command(visible_alias = "d")]
+    Delete(filters::delete::Params),
+    /// Checks the syntax of a filter query and that its projects, sections and labels exist.
+    Check(filters::check::Params),
+}
+
+#[derive(Args, Debug)]
+struct ExportArgs {
+    #[command(subcommand)]
+    command: ExportCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum ExportCommands {
+    /// Exports tasks with due dates as an iCalendar file.
+    Ics(export::ics::Params),
+    /// Exports a project with its tasks as a Markdown document.
+    #[command(visible_alias = "md")]
+    Markdown(export::outline::Params),
+    /// Exports a project with its tasks as an Org-mode document.
+    Org(export::outline::Params),
+    /// Exports tasks in the todo.txt format.
+    Todotxt(interchange::ExportParams),
+    /// Exports tasks as Taskwarrior JSON.
+    Taskwarrior(interchange::ExportParams),
+}
+
+#[derive(Args, Debug)]
+struct ImportArgs {
+    #[command(subcommand)]
+    command: ImportCommands,
+}
+
+#[derive(Subcommand, Debug)]
+enum ImportCommands {
+    /// Imports tasks from a todo.txt file.
+    Todotxt(interchange::ImportParams),
+    /// Imports tasks from the JSON of `task export`.
+    Taskwarrior(interchange::ImportParams),
+}
+
 #[derive(Args, Debug)]
 #[command(args_conflicts_with_subcommands = true)]
 struct SectionArgs {
@@ -153,59 +258,134 @@ impl Arguments {
             Some(p) => Config::load_prefix(&p),
             None => Config::load(),
         }?;
+        if self.output.is_tabular()
+            && !matches!(
+                self.command,
+                None | Some(Commands::Authenticated(AuthCommands::List(_)))
+            )
+        {
+            return Err(eyre!(
+                "CSV and TSV output is only supported when listing tasks"
+            ));
+        }
+        cfg.output = self.output;
         match self.command {
             Some(command) => match command {
-                Commands::Auth { token } => {
-                    cfg.token = Some(token);
-                    cfg.save()?;
+                Commands::Auth {
+                    token,
+                    oauth,
+                    oauth_params,
+                } => {
+                    if oauth {
+                        oauth::login(oauth_params, &mut cfg).await?;
+                    } else {
+                        cfg.token = token;
+                        cfg.save()?;
+                    }
                     println!("Token successfully saved")
                 }
+                Commands::Completions(p) => completions::completions(p)?,
+                Commands::Complete(p) => completions::complete(p, &cfg).await?,
                 Commands::Authenticated(command) => {
                     let gw = cfg.gateway()?;
                     match command {
-                        AuthCommands::Add(p) => add::add(p, &gw, &cfg).await?,
+                        AuthCommands::Add(p) => add::add(*p, &gw, &cfg).await?,
                         AuthCommands::Create(p) => create::create(p, &gw, &cfg).await?,
                         AuthCommands::List(p) => list::list(p, &gw, &cfg).await?,
                         AuthCommands::Edit(p) => edit::edit(p, &gw, &cfg).await?,
                         AuthCommands::Close(p) => close::close(p, &gw, &cfg).await?,
+                        AuthCommands::Agenda(p) => agenda::agenda(p, &gw, &cfg).await?,
                         AuthCommands::View(p) => view::view(p, &gw, &cfg).await?,
                         AuthCommands::Comment(p) => comment::comment(p, &gw, &cfg).await?,
+                        AuthCommands::Search(p) => search::search(p, &gw, &cfg).await?,
                         AuthCommands::Projects(p) => match p.command {
                             Some(p) => match p {
-                                ProjectCommands::List(p) => projects::list::list(p, &gw).await?,
-                                ProjectCommands::View(p) => projects::view::view(p, &gw).await?,
+                                ProjectCommands::List(p) => {
+                                    projects::list::list(p, &gw, &cfg).await?
+                                }
+                                ProjectCommands::View(p) => {
+                                    projects::view::view(p, &gw, &cfg).await?
+                                }
                                 ProjectCommands::Comment(p) => {
-                                    projects::comment::comment(p, &gw).await?
+                                    projects::comment::comment(p, &gw, &cfg).await?
                                 }
-                                ProjectCommands::Add(p) => projects::add::add(p, &gw).await?,
+                                ProjectCommands::Add(p) => projects::add::add(p, &gw, &cfg).await?,
                                 ProjectCommands::Delete(p) => {
-                                    projects::delete::delete(p, &gw).await?
+                                    projects::delete::delete(p, &gw, &cfg).await?
                                 }
                                 ProjectCommands::Sections(s) => match s.command {
                                     Some(s) => match s {
                                         SectionCommands::List(p) => {
-                                            sections::list::list(p, &gw).await?
+                                            sections::list::list(p, &gw, &cfg).await?
                                         }
                                         SectionCommands::Add(p) => {
-                                            sections::add::add(p, &gw).await?
+                                            sections::add::add(p, &gw, &cfg).await?
                                         }
                                         SectionCommands::Delete(p) => {
-                                            sections::delete::delete(p, &gw).await?
+                                            sections::delete::delete(p, &gw, &cfg).await?
                                         }
                                     },
-                                    None => sections::list::list(s.params, &gw).await?,
+                                    None => sections::list::list(s.params, &gw, &cfg).await?,
                                 },
                             },
-                            None => projects::list::list(p.params, &gw).await?,
+                            None => projects::list::list(p.params, &gw, &cfg).await?,
                         },
                         AuthCommands::Labels(p) => match p.command {
                             Some(p) => match p {
-                                LabelCommands::List(p) => labels::list::list(p, &gw).await?,
-                                LabelCommands::Add(p) => labels::add::add(p, &gw).await?,
-                                LabelCommands::Delete(p) => labels::delete::delete(p, &gw).await?,
+                                LabelCommands::List(p) => labels::list::list(p, &gw, &cfg).await?,
+                                LabelCommands::Add(p) => labels::add::add(p, &gw, &cfg).await?,
+                                LabelCommands::Delete(p) => {
+                                    labels::delete::delete(p, &gw, &cfg).await?
+                                }
+                            },
+                            None => labels::list::list(p.params, &gw, &cfg).await?,
+                        },
+                        AuthCommands::Filters(p) => match p.command {
+                            Some(p) => match p {
+                                FilterCommands::List(p) => {
+                                    filters::list::list(p, &gw, &cfg).await?
+                                }
+                                FilterCommands::Add(p) => filters::add::add(p, &gw, &cfg).await?,
+                                FilterCommands::Edit(p) => {
+                                    filters::edit::edit(p, &gw, &cfg).await?
+                                }
+                                FilterCommands::Delete(p) => {
+                                    filters::delete::delete(p, &gw, &cfg).await?
+                                }
+                                FilterCommands::Check(p) => {
+                                    filters::check::check(p, &gw, &cfg).await?
+                                }
                             },
-                            None => labels::list::list(p.params, &gw).await?,
+                            None => filters::list::list(p.params, &gw, &cfg).await?,
+                        },
+                        AuthCommands::Export(p) => match p.command {
+                            ExportCommands::Ics(p) => export::ics::ics(p, &gw, &cfg).await?,
+                            ExportCommands::Markdown(p) => {
+                                export::outline::export(p, outline::Format::Markdown, &gw, &cfg)
+                                    .await?
+                            }
+                            ExportCommands::Org(p) => {
+                                export::outline::export(p, outline::Format::Org, &gw, &cfg).await?
+                            }
+                            ExportCommands::Todotxt(p) => {
+                                interchange::export(p, interchange::Format::Todotxt, &gw, &cfg)
+                                    .await?
+                            }
+                            ExportCommands::Taskwarrior(p) => {
+                                interchange::export(p, interchange::Format::Taskwarrior, &gw, &cfg)
+                                    .await?
+                            }
+                        },
+                        AuthCommands::Import(p) => match p.command {
+                            ImportCommands::Todotxt(p) => {
+                                interchange::import(p, interchange::Format::Todotxt, &gw).await?
+                            }
+                            ImportCommands::Taskwarrior(p) => {
+                                interchange::import(p, interchange::Format::Taskwarrior, &gw)
+                                    .await?
+                            }
                         },
+                        AuthCommands::Tui(p) => tui::tui(p, &gw, &cfg).await?,
                     }
                 }
             },
@@ -0,0 +1,373 @@
+//! Shell completions for the CLI.
+//!
+//! The generated scripts call back into the hidden `__complete` command with the words of the
+//! command line, so that flags and subcommands are completed from the clap definition of
+//! [`Arguments`], and project, section and label names as well as task IDs from the current state
+//! in Todoist.
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use color_eyre::Result;
+
+use crate::{Arguments, api::rest::Gateway, config::Config};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The shell to generate the completion script for.
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct CompleteParams {
+    /// The words of the command line up to the cursor, starting with the name of the binary.
+    #[arg(last = true)]
+    words: Vec<String>,
+}
+
+/// The shells that completion scripts can be generated for.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Values that are completed from the current state in Todoist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dynamic {
+    Projects,
+    ProjectIDs,
+    Sections,
+    SectionIDs,
+    Labels,
+    LabelIDs,
+    Tasks,
+}
+
+/// A single completion, with an optional description that is shown by shells that support it.
+#[derive(Debug, PartialEq, Eq)]
+struct Candidate {
+    value: String,
+    help: Option<String>,
+}
+
+/// The completions for the word under the cursor.
+#[derive(Debug, Default)]
+struct Completions {
+    candidates: Vec<Candidate>,
+    dynamic: Option<Dynamic>,
+}
+
+/// Prints the completion script for the shell.
+pub fn completions(params: Params) -> Result<()> {
+    let name = Arguments::command().get_name().to_string();
+    let script = match params.shell {
+        Shell::Bash => format!(
+            r#"_{name}() {{
+    local IFS=$'\n' line
+    COMPREPLY=()
+    for line in $({name} __complete -- "${{COMP_WORDS[@]:0:COMP_CWORD+1}}" 2>/dev/null); do
+        # Values like project names can contain spaces, which have to be escaped.
+        COMPREPLY+=("$(printf '%q' "${{line%%$'\t'*}}")")
+    done
+}}
+complete -F _{name} {name}"#
+        ),
+        Shell::Zsh => format!(
+            r#"#compdef {name}
+_{name}() {{
+    local -a candidates
+    local line
+    for line in "${{(@f)$({name} __complete -- "${{(@)words[1,CURRENT]}}" 2>/dev/null)}}"; do
+        [[ -z $line ]] && continue
+        if [[ $line == *$'\t'* ]]; then
+            candidates+=("${{${{line%%$'\t'*}}//:/\\:}}:${{line#*$'\t'}}")
+        else
+            candidates+=("${{line//:/\\:}}")
+        fi
+    done
+    _describe 'values' candidates
+}}
+compdef _{name} {name}"#
+        ),
+        // The word at the cursor is quoted, so that it's passed on even when it's empty.
+        Shell::Fish => format!(
+            "complete -c {name} -f -a '({name} __complete -- (commandline -opc) \"$(commandline -ct)\" 2>/dev/null)'"
+        ),
+    };
+    println!("{script}");
+    Ok(())
+}
+
+/// Prints the completions for the last word of the command line, one per line with the
+/// description separated by a tab.
+pub async fn complete(params: CompleteParams, cfg: &Config) -> Result<()> {
+    // The binary name is not part of the arguments that clap knows about.
+    let Some((current, words)) = params.words.get(1..).and_then(|w| w.split_last()) else {
+        return Ok(());
+    };
+    let mut cmd = Arguments::command();
+    cmd.build();
+    let mut completions = completions_for(&cmd, words, current);
+    if let Some(dynamic) = completions.dynamic {
+        // Completions can't show errors, so values that can't be fetched are left out.
+        if let Ok(gw) = cfg.gateway() {
+            completions
+                .candidates
+                .extend(dynamic.candidates(&gw, cfg).await.unwrap_or_default());
+        }
+    }
+    let prefix = current.to_lowercase();
+    for candidate in completions
+        .candidates
+        .into_iter()
+        .filter(|c| c.value.to_lowercase().starts_with(&prefix))
+    {
+        match candidate.help {
+            Some(help) => println!("{}\t{}", candidate.value, help),
+            None => println!("{}", candidate.value),
+        }
+    }
+    Ok(())
+}
+
+/// Finds the completions for the word under the cursor, based on the words before it.
+fn completions_for(cmd: &Command, words: &[String], current: &str) -> Completions {
+    let mut cmd = cmd;
+    let mut positionals = 0;
+    // Set if the previous word is an option that still needs its value.
+    let mut pending: Option<&Arg> = None;
+    for word in words {
+        if pending.take().is_some() {
+            continue;
+        }
+        if let Some(long) = word.strip_prefix("--") {
+            if !long.contains('=') {
+                pending = cmd
+                    .get_arguments()
+                    .find(|a| a.get_long() == Some(long))
+                    .filter(|a| a.get_action().takes_values());
+            }
+        } else if let Some(shorts) = word.strip_prefix('-').filter(|s| !s.is_empty()) {
+            // Short flags can be grouped like `-np1`, where only the last one can take a value
+            // that is in the next word.
+            for (index, short) in shorts.char_indices() {
+                let Some(arg) = cmd.get_arguments().find(|a| a.get_short() == Some(short)) else {
+                    break;
+                };
+                if arg.get_action().takes_values() {
+                    if index + short.len_utf8() == shorts.len() {
+                        pending = Some(arg);
+                    }
+                    break;
+                }
+            }
+        } else if let Some(subcommand) = cmd.find_subcommand(word) {
+            cmd = subcommand;
+            positionals = 0;
+        } else {
+            positionals += 1;
+        }
+    }
+    if let Some(arg) = pending {
+        return values(arg);
+    }
+    if current.starts_with('-') {
+        return Completions {
+            candidates: flags(cmd),
+            dynamic: None,
+        };
+    }
+    let mut completions = cmd
+        .get_positionals()
+        .nth(positionals)
+        .map(values)
+        .unwrap_or_default();
+    completions
+        .candidates
+        .extend(
+            cmd.get_subcommands()
+                .filter(|s| !s.is_hide_set())
+                .map(|s| Candidate {
+                    value: s.get_name().to_string(),
+                    help: s.get_about().map(|a| a.to_string()),
+                }),
+        );
+    completions
+}
+
+/// Returns the completions for the value of an argument.
+fn values(arg: &Arg) -> Completions {
+    let dynamic = match arg.get_id().as_str() {
+        "project" => Some(Dynamic::Projects),
+        "project_id" => Some(Dynamic::ProjectIDs),
+        "section" => Some(Dynamic::Sections),
+        "section_id" => Some(Dynamic::SectionIDs),
+        "label_names" | "add_labels" => Some(Dynamic::Labels),
+        "label_ids" => Some(Dynamic::LabelIDs),
+        "id" | "parent" => Some(Dynamic::Tasks),
+        _ => None,
+    };
+    Completions {
+        candidates: arg
+            .get_possible_values()
+            .into_iter()
+            .filter(|v| !v.is_hide_set())
+            .map(|v| Candidate {
+                value: v.get_name().to_string(),
+                help: v.get_help().map(|h| h.to_string()),
+            })
+            .collect(),
+        dynamic,
+    }
+}
+
+/// Returns the flags of the command, using the long form where there is one.
+fn flags(cmd: &Command) -> Vec<Candidate> {
+    cmd.get_arguments()
+        .filter(|a| !a.is_positional() && !a.is_hide_set())
+        .filter_map(|a| {
+            let value = match (a.get_long(), a.get_short()) {
+                (Some(long), _) => format!("--{long}"),
+                (None, Some(short)) => format!("-{short}"),
+                (None, None) => return None,
+            };
+            Some(Candidate {
+                value,
+                help: a.get_help().map(|h| h.to_string()),
+            })
+        })
+        .collect()
+}
+
+impl Dynamic {
+    /// Fetches the values from the Todoist API.
+    async fn candidates(self, gw: &Gateway, cfg: &Config) -> Result<Vec<Candidate>> {
+        let candidate = |value: &str, help: Option<&str>| Candidate {
+            value: value.to_string(),
+            help: help.map(str::to_string),
+        };
+        Ok(match self {
+            Dynamic::Projects => gw
+                .projects()
+                .await?
+                .iter()
+                .map(|p| candidate(&p.name, None))
+                .collect(),
+            Dynamic::ProjectIDs => gw
+                .projects()
+                .await?
+                .iter()
+                .map(|p| candidate(&p.id, Some(&p.name)))
+                .collect(),
+            Dynamic::Sections => gw
+                .sections()
+                .await?
+                .iter()
+                .map(|s| candidate(&s.name, None))
+                .collect(),
+            Dynamic::SectionIDs => gw
+                .sections()
+                .await?
+                .iter()
+                .map(|s| candidate(&s.id, Some(&s.name)))
+                .collect(),
+            Dynamic::Labels => gw
+                .labels()
+                .await?
+                .iter()
+                .map(|l| candidate(&l.name, None))
+                .collect(),
+            Dynamic::LabelIDs => gw
+                .labels()
+                .await?
+                .iter()
+                .map(|l| candidate(&l.id, Some(&l.name)))
+                .collect(),
+            // Tasks are selected with the default filter when no ID is given, so the same tasks
+            // are offered here.
+            Dynamic::Tasks => gw
+                .tasks(Some(&cfg.default_filter))
+                .await?
+                .iter()
+                .map(|t| candidate(&t.id, Some(&t.content)))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn complete(line: &[&str], current: &str) -> Completions {
+        let mut cmd = Arguments::command();
+        cmd.build();
+        let words = line.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+        completions_for(&cmd, &words, current)
+    }
+
+    fn values(completions: &Completions) -> Vec<&str> {
+        completions
+            .candidates
+            .iter()
+            .map(|c| c.value.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn subcommands() {
+        let completions = complete(&[], "");
+        assert!(values(&completions).contains(&"projects"));
+        assert!(!values(&completions).contains(&"__complete"));
+        assert_eq!(completions.dynamic, None);
+
+        let completions = complete(&["p"], "");
+        assert!(values(&completions).contains(&"sections"));
+        let completions = complete(&["projects", "sections"], "");
+        assert!(values(&completions).contains(&"delete"));
+    }
+
+    #[test]
+    fn flags() {
+        let completions = complete(&["add"], "--");
+        assert!(values(&completions).contains(&"--project"));
+        assert!(values(&completions).contains(&"--output"));
+        assert_eq!(completions.dynamic, None);
+    }
+
+    #[test]
+    fn option_values() {
+        assert_eq!(
+            complete(&["add", "-P"], "wo").dynamic,
+            Some(Dynamic::Projects)
+        );
+        assert_eq!(
+            complete(&["add", "Task", "--section"], "").dynamic,
+            Some(Dynamic::Sections)
+        );
+        assert_eq!(
+            complete(&["list", "-nL"], "").dynamic,
+            Some(Dynamic::Labels)
+        );
+        assert_eq!(
+            complete(&["projects", "view", "--project_id"], "").dynamic,
+            Some(Dynamic::ProjectIDs)
+        );
+        assert_eq!(
+            values(&complete(&["list", "--output"], "")),
+            vec!["text", "json", "jsonl", "csv", "tsv"]
+        );
+        // The value of an option is not completed once it was given.
+        assert_eq!(complete(&["add", "-P", "work"], "").dynamic, None);
+    }
+
+    #[test]
+    fn positionals() {
+        assert_eq!(complete(&["close"], "").dynamic, Some(Dynamic::Tasks));
+        assert_eq!(
+            complete(&["edit", "-n", "Name"], "").dynamic,
+            Some(Dynamic::Tasks)
+        );
+        assert_eq!(complete(&["close", "123"], "").dynamic, None);
+    }
+}
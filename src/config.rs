@@ -1,5 +1,6 @@
 //! Describes everything related to configuration of the binary.
 use std::{
+    collections::HashMap,
     fs,
     path::{Path, PathBuf},
 };
@@ -9,7 +10,11 @@ use color_eyre::{Result, eyre::eyre};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use crate::api::rest::{Gateway, TODOIST_API_URL};
+use crate::{
+    api::rest::{Gateway, TODOIST_API_URL},
+    oauth::TODOIST_OAUTH_URL,
+    output::Output,
+};
 
 /// Stores configuration used by the application.
 #[derive(Serialize, Deserialize, Default)]
@@ -28,10 +33,34 @@ pub struct Config {
     /// Override the current time for various display options in the CLI.
     #[serde(default)]
     pub override_time: Option<DateTime<Utc>>,
+    /// Named templates for task rows that can be used with `list --format <name>`.
+    #[serde(default)]
+    pub formats: HashMap<String, String>,
+    /// Template or name of a template in `formats` that is used for task rows if no other format
+    /// is specified.
+    #[serde(default)]
+    pub default_format: Option<String>,
+    /// Client ID of the Todoist app that is used by `doist auth --oauth`. Apps can be registered
+    /// in the [Todoist App Console](https://developer.todoist.com/appconsole.html).
+    #[serde(default)]
+    pub oauth_client_id: Option<String>,
+    /// Client secret of the Todoist app that is used by `doist auth --oauth`.
+    #[serde(default)]
+    pub oauth_client_secret: Option<String>,
+    /// Port on localhost that receives the OAuth redirect. Uses `DEFAULT_OAUTH_PORT` if none
+    /// specified.
+    #[serde(default)]
+    pub oauth_port: Option<u16>,
+    /// Can override the OAuth authorization server URL. Mostly used for testing.
+    #[serde(default = "default_oauth_url")]
+    pub oauth_url: Option<url::Url>,
 
     /// Sets a particular config location prefix. Mostly used for testing.
     #[serde(skip)]
     pub prefix: Option<PathBuf>,
+    /// Sets the format commands write their results in. Set from the command line.
+    #[serde(skip)]
+    pub output: Output,
 }
 
 /// Returns the default URL to be used for calling the Todoist API.
@@ -39,6 +68,14 @@ fn default_url() -> Option<url::Url> {
     Some(TODOIST_API_URL.clone())
 }
 
+/// Returns the default URL to be used for the Todoist OAuth authorization server.
+fn default_oauth_url() -> Option<url::Url> {
+    Some(TODOIST_OAUTH_URL.clone())
+}
+
+/// Default port of the local OAuth redirect listener when no config override is done.
+pub const DEFAULT_OAUTH_PORT: u16 = 8484;
+
 /// Default filter when no config override is done.
 const DEFAULT_FILTER: &str = "(today | overdue)";
 
@@ -94,6 +131,38 @@ impl Config {
             })
     }
 
+    #[cfg(windows)]
+    fn state_dir(prefix: Option<&Path>) -> Result<PathBuf, ConfigError> {
+        dirs::data_local_dir()
+            .map(|mut path| {
+                path.push(prefix.and_then(|p| p.to_str()).unwrap_or(XDG_PREFIX));
+                path
+            })
+            .ok_or_else(|| ConfigError::File {
+                file: PathBuf::from(XDG_PREFIX),
+                io: None,
+            })
+    }
+
+    /// Returns the name of the directories that are used for state that is kept between runs.
+    #[cfg(not(windows))]
+    fn state_dir(prefix: Option<&Path>) -> Result<PathBuf, ConfigError> {
+        xdg::BaseDirectories::with_prefix(prefix.and_then(|p| p.to_str()).unwrap_or(XDG_PREFIX))
+            .get_state_home()
+            .ok_or_else(|| ConfigError::File {
+                file: PathBuf::from(XDG_PREFIX),
+                io: None,
+            })
+    }
+
+    /// Returns the path of a file in the state directory, which lives next to the config file
+    /// when a config prefix is set.
+    pub fn state_file(&self, name: &str) -> Result<PathBuf, ConfigError> {
+        let mut path = Self::state_dir(self.prefix.as_deref())?;
+        path.push(name);
+        Ok(path)
+    }
+
     /// Returns the name of the config file that is used for configuration.
     fn config_file(prefix: Option<&Path>) -> Result<PathBuf, ConfigError> {
         let mut path = Self::config_dir(prefix)?;
@@ -164,4 +233,11 @@ impl Config {
             &self.url.clone().unwrap_or_else(|| default_url().unwrap()),
         ))
     }
+
+    /// Returns the OAuth authorization server URL to use.
+    pub fn oauth_url(&self) -> url::Url {
+        self.oauth_url
+            .clone()
+            .unwrap_or_else(|| default_oauth_url().unwrap())
+    }
 }
@@ -0,0 +1,489 @@
+use std::{collections::BTreeMap, fs, ops::RangeInclusive, path::PathBuf};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Offset, TimeZone, Utc};
+use chrono_tz::{OffsetComponents, OffsetName, Tz};
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{DueDate, Gateway, Priority, Task},
+    config::Config,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// File to write the calendar to. Writes to stdout if omitted.
+    file: Option<PathBuf>,
+    /// Only exports tasks passing this filter. Tasks without a due date are always skipped.
+    #[arg(short = 'f', long = "filter", default_value = "all")]
+    filter: String,
+    /// Writes tasks as VEVENT entries instead of VTODO, which more calendar apps display.
+    #[arg(long = "events")]
+    events: bool,
+}
+
+/// Exports all tasks with due dates as an iCalendar file.
+pub async fn ics(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let mut tasks = gw.tasks(Some(&params.filter)).await?;
+    tasks.retain(|t| t.due.is_some());
+    tasks.sort();
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let calendar = calendar(&tasks, params.events, &now);
+    match params.file {
+        Some(file) => {
+            fs::write(&file, calendar)
+                .wrap_err_with(|| format!("unable to write {}", file.display()))?;
+            println!("exported {} tasks to {}", tasks.len(), file.display());
+        }
+        None => print!("{calendar}"),
+    }
+    Ok(())
+}
+
+/// Renders the given tasks as a full iCalendar document. Tasks without a due date are skipped.
+fn calendar(tasks: &[Task], events: bool, now: &DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//doist//doist//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Todoist".to_string(),
+    ];
+    // Times are written in the timezone of the task, which has to be defined in the calendar.
+    let mut zones: BTreeMap<&str, (Tz, i32, i32)> = BTreeMap::new();
+    for task in tasks {
+        if let Some(exact) = task.due.as_ref().and_then(|due| due.exact.as_ref())
+            && let Ok(tz) = exact.timezone.parse::<Tz>()
+        {
+            let year = exact.datetime.with_timezone(&tz).year();
+            let (_, first, last) = zones.entry(tz.name()).or_insert((tz, year, year));
+            *first = (*first).min(year);
+            *last = (*last).max(year);
+        }
+    }
+    for (tz, first, last) in zones.into_values() {
+        lines.extend(timezone(tz, first..=last + TIMEZONE_YEARS));
+    }
+    for task in tasks {
+        if let Some(due) = &task.due {
+            lines.extend(component(task, due, events, now));
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold(l)).collect()
+}
+
+/// Renders a single task as a VTODO or VEVENT component.
+fn component(task: &Task, due: &DueDate, events: bool, now: &DateTime<Utc>) -> Vec<String> {
+    let kind = if events { "VEVENT" } else { "VTODO" };
+    let mut lines = vec![
+        format!("BEGIN:{kind}"),
+        format!("UID:{}@doist", task.id),
+        format!("DTSTAMP:{}", utc(now)),
+        format!("SUMMARY:{}", escape(&task.content)),
+    ];
+    if !task.description.is_empty() {
+        lines.push(format!("DESCRIPTION:{}", escape(&task.description)));
+    }
+    lines.push(format!("URL:{}", task.url));
+    let start = if events { "DTSTART" } else { "DUE" };
+    match &due.exact {
+        Some(exact) => match exact.timezone.parse::<Tz>() {
+            Ok(tz) => lines.push(format!(
+                "{start};TZID={}:{}",
+                tz.name(),
+                exact.datetime.with_timezone(&tz).format("%Y%m%dT%H%M%S")
+            )),
+            Err(_) => lines.push(format!(
+                "{start}:{}",
+                utc(&exact.datetime.with_timezone(&Utc))
+            )),
+        },
+        None => {
+            lines.push(format!("{start};VALUE=DATE:{}", date(&due.date)));
+            if events {
+                lines.push(format!(
+                    "DTEND;VALUE=DATE:{}",
+                    date(&(due.date + Duration::days(1)))
+                ));
+            }
+        }
+    }
+    if due.is_recurring
+        && let Some(rule) = rrule(&due.string)
+    {
+        if !events {
+            // Recurrence in VTODO is anchored at DTSTART, so it has to be set as well.
+            let dtstart = lines.last().unwrap().replacen("DUE", "DTSTART", 1);
+            lines.push(dtstart);
+        }
+        lines.push(format!("RRULE:{rule}"));
+    }
+    match task.priority {
+        Priority::Urgent => lines.push("PRIORITY:1".to_string()),
+        Priority::VeryHigh => lines.push("PRIORITY:3".to_string()),
+        Priority::High => lines.push("PRIORITY:5".to_string()),
+        Priority::Normal => {}
+    }
+    if !task.labels.is_empty() {
+        lines.push(format!(
+            "CATEGORIES:{}",
+            task.labels
+                .iter()
+                .map(|l| escape(l))
+                .collect::<Vec<_>>()
+                .join(",")
+        ));
+    }
+    lines.push(format!("END:{kind}"));
+    lines
+}
+
+/// How many years after the last due date in a timezone its offset changes are listed, so that
+/// recurring tasks keep their local time.
+const TIMEZONE_YEARS: i32 = 5;
+
+/// Renders the VTIMEZONE component for the timezone, listing every change of its UTC offset in
+/// the given years. The offset from before the first year is listed as well, so zones without
+/// daylight saving time still get an observance.
+fn timezone(tz: Tz, years: RangeInclusive<i32>) -> Vec<String> {
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{}", tz.name())];
+    let Some(mut time) = Utc.with_ymd_and_hms(*years.start(), 1, 1, 0, 0, 0).single() else {
+        return Vec::new();
+    };
+    let end = Utc
+        .with_ymd_and_hms(*years.end() + 1, 1, 1, 0, 0, 0)
+        .single()
+        .unwrap_or(time);
+    lines.extend(observance(
+        &time.with_timezone(&tz),
+        &time.with_timezone(&tz),
+    ));
+    while time < end {
+        let next = time + Duration::days(1);
+        let from = time.with_timezone(&tz);
+        if offset(&from) != offset(&next.with_timezone(&tz)) {
+            // Narrows the day down to the first second with the new offset.
+            let (mut before, mut after) = (time, next);
+            while after - before > Duration::seconds(1) {
+                let middle = before + (after - before) / 2;
+                if offset(&middle.with_timezone(&tz)) == offset(&from) {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            lines.extend(observance(&from, &after.with_timezone(&tz)));
+        }
+        time = next;
+    }
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// Renders the observance that starts at `to`, coming from the offset of `from`.
+fn observance(from: &DateTime<Tz>, to: &DateTime<Tz>) -> Vec<String> {
+    let kind = if to.offset().dst_offset().is_zero() {
+        "STANDARD"
+    } else {
+        "DAYLIGHT"
+    };
+    let mut lines = vec![
+        format!("BEGIN:{kind}"),
+        // The start is given in the local time that was in effect before the change.
+        format!(
+            "DTSTART:{}",
+            to.with_timezone(&from.offset().fix())
+                .format("%Y%m%dT%H%M%S")
+        ),
+        format!("TZOFFSETFROM:{}", utc_offset(from)),
+        format!("TZOFFSETTO:{}", utc_offset(to)),
+    ];
+    if let Some(name) = to.offset().abbreviation() {
+        lines.push(format!("TZNAME:{name}"));
+    }
+    lines.push(format!("END:{kind}"));
+    lines
+}
+
+/// Identifies the offset of the time, including whether it's daylight saving time.
+fn offset(time: &DateTime<Tz>) -> (i32, bool) {
+    (
+        time.offset().fix().local_minus_utc(),
+        time.offset().dst_offset().is_zero(),
+    )
+}
+
+/// Formats the UTC offset of the time like `+0300`.
+fn utc_offset(time: &DateTime<Tz>) -> String {
+    let seconds = time.offset().fix().local_minus_utc();
+    let sign = if seconds < 0 { '-' } else { '+' };
+    let minutes = seconds.abs() / 60;
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+/// Maps the human readable recurring due string to an RRULE where it's expressible.
+///
+/// Handles strings like `every day`, `every other week`, `every 3 months`, `every monday, friday`
+/// or `every weekday`, ignoring times of day. Anything more complex returns [`None`].
+fn rrule(due: &str) -> Option<String> {
+    let due = due.to_lowercase();
+    let rest = due
+        .strip_prefix("every!")
+        .or_else(|| due.strip_prefix("every"))?;
+    let mut interval = 1;
+    let mut freq = None;
+    let mut days = Vec::new();
+    for word in rest
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|w| !w.is_empty())
+    {
+        match word {
+            "other" => interval = 2,
+            "at" | "and" => {}
+            "day" | "days" => freq = Some("DAILY"),
+            "week" | "weeks" => freq = Some("WEEKLY"),
+            "month" | "months" => freq = Some("MONTHLY"),
+            "year" | "years" => freq = Some("YEARLY"),
+            "weekday" | "workday" => days.extend(["MO", "TU", "WE", "TH", "FR"]),
+            w if w.parse::<u32>().is_ok() && freq.is_none() && days.is_empty() => {
+                interval = w.parse().unwrap()
+            }
+            w if is_time(w) => {}
+            w => days.push(weekday(w)?),
+        }
+    }
+    match (freq, days.is_empty()) {
+        (Some(freq), true) => Some(match interval {
+            1 => format!("FREQ={freq}"),
+            n => format!("FREQ={freq};INTERVAL={n}"),
+        }),
+        (None, false) => Some(match interval {
+            1 => format!("FREQ=WEEKLY;BYDAY={}", days.join(",")),
+            n => format!("FREQ=WEEKLY;INTERVAL={n};BYDAY={}", days.join(",")),
+        }),
+        _ => None,
+    }
+}
+
+fn weekday(word: &str) -> Option<&'static str> {
+    Some(match word {
+        "monday" | "mon" => "MO",
+        "tuesday" | "tue" | "tues" => "TU",
+        "wednesday" | "wed" => "WE",
+        "thursday" | "thu" | "thurs" => "TH",
+        "friday" | "fri" => "FR",
+        "saturday" | "sat" => "SA",
+        "sunday" | "sun" => "SU",
+        _ => return None,
+    })
+}
+
+/// Checks if the word is a time of day like `8am`, `7:30pm` or `22:00`.
+fn is_time(word: &str) -> bool {
+    let word = word
+        .strip_suffix("am")
+        .or_else(|| word.strip_suffix("pm"))
+        .unwrap_or(word);
+    !word.is_empty()
+        && word.contains(|c: char| c.is_ascii_digit())
+        && word.chars().all(|c| c.is_ascii_digit() || c == ':')
+        && (word.contains(':') || word.len() <= 2)
+}
+
+fn utc(dt: &DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn date(date: &NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+/// Escapes text values as required by RFC 5545.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Folds content lines longer than 75 octets and terminates them with CRLF.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::ExactTime;
+
+    #[test]
+    fn rrules() {
+        assert_eq!(rrule("every day").unwrap(), "FREQ=DAILY");
+        assert_eq!(rrule("Every day 8am").unwrap(), "FREQ=DAILY");
+        assert_eq!(rrule("every day 22:00").unwrap(), "FREQ=DAILY");
+        assert_eq!(rrule("every other day").unwrap(), "FREQ=DAILY;INTERVAL=2");
+        assert_eq!(rrule("every 2 weeks").unwrap(), "FREQ=WEEKLY;INTERVAL=2");
+        assert_eq!(rrule("every month").unwrap(), "FREQ=MONTHLY");
+        assert_eq!(rrule("every! 3 years").unwrap(), "FREQ=YEARLY;INTERVAL=3");
+        assert_eq!(rrule("every thursday 7pm").unwrap(), "FREQ=WEEKLY;BYDAY=TH");
+        assert_eq!(
+            rrule("every mon, fri at 7:30pm").unwrap(),
+            "FREQ=WEEKLY;BYDAY=MO,FR"
+        );
+        assert_eq!(
+            rrule("every weekday").unwrap(),
+            "FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR"
+        );
+        assert_eq!(
+            rrule("every other monday").unwrap(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO"
+        );
+        assert_eq!(
+            rrule("every 2 fri").unwrap(),
+            "FREQ=WEEKLY;INTERVAL=2;BYDAY=FR"
+        );
+        assert_eq!(rrule("every 3rd friday"), None);
+        assert_eq!(rrule("every day starting aug 3"), None);
+        assert_eq!(rrule("tomorrow"), None);
+    }
+
+    #[test]
+    fn folds_lines() {
+        assert_eq!(fold("short"), "short\r\n");
+        let long = "x".repeat(100);
+        assert_eq!(
+            fold(&long),
+            format!("{}\r\n {}\r\n", "x".repeat(75), "x".repeat(25))
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        assert_eq!(escape("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn timezones() {
+        assert_eq!(
+            timezone("Europe/Athens".parse().unwrap(), 2022..=2022),
+            [
+                "BEGIN:VTIMEZONE",
+                "TZID:Europe/Athens",
+                "BEGIN:STANDARD",
+                "DTSTART:20220101T020000",
+                "TZOFFSETFROM:+0200",
+                "TZOFFSETTO:+0200",
+                "TZNAME:EET",
+                "END:STANDARD",
+                "BEGIN:DAYLIGHT",
+                "DTSTART:20220327T030000",
+                "TZOFFSETFROM:+0200",
+                "TZOFFSETTO:+0300",
+                "TZNAME:EEST",
+                "END:DAYLIGHT",
+                "BEGIN:STANDARD",
+                "DTSTART:20221030T040000",
+                "TZOFFSETFROM:+0300",
+                "TZOFFSETTO:+0200",
+                "TZNAME:EET",
+                "END:STANDARD",
+                "END:VTIMEZONE",
+            ]
+        );
+        assert_eq!(
+            timezone("Asia/Kolkata".parse().unwrap(), 2022..=2030)[2..],
+            [
+                "BEGIN:STANDARD",
+                "DTSTART:20220101T053000",
+                "TZOFFSETFROM:+0530",
+                "TZOFFSETTO:+0530",
+                "TZNAME:IST",
+                "END:STANDARD",
+                "END:VTIMEZONE",
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_calendar() {
+        let now = "2022-08-26T19:33:20Z".parse().unwrap();
+        let tasks = vec![
+            Task {
+                due: Some(DueDate {
+                    string: "every thursday 8am".to_string(),
+                    date: "2022-09-15".parse().unwrap(),
+                    is_recurring: true,
+                    exact: Some(ExactTime {
+                        datetime: "2022-09-15T05:00:00Z".parse().unwrap(),
+                        timezone: "Europe/Athens".to_string(),
+                    }),
+                }),
+                priority: Priority::Urgent,
+                labels: vec!["work".to_string()],
+                ..Task::new("1", "Meet, greet")
+            },
+            Task {
+                due: Some(DueDate {
+                    string: "20 Sep".to_string(),
+                    date: "2022-09-20".parse().unwrap(),
+                    is_recurring: false,
+                    exact: None,
+                }),
+                ..Task::new("2", "Get some data")
+            },
+            Task::new("3", "No due date"),
+        ];
+        let athens = timezone("Europe/Athens".parse().unwrap(), 2022..=2027);
+        assert_eq!(
+            calendar(&tasks, false, &now),
+            [
+                "BEGIN:VCALENDAR",
+                "VERSION:2.0",
+                "PRODID:-//doist//doist//EN",
+                "CALSCALE:GREGORIAN",
+                "X-WR-CALNAME:Todoist",
+            ]
+            .into_iter()
+            .chain(athens.iter().map(String::as_str))
+            .chain([
+                "BEGIN:VTODO",
+                "UID:1@doist",
+                "DTSTAMP:20220826T193320Z",
+                "SUMMARY:Meet\\, greet",
+                "URL:http://localhost/",
+                "DUE;TZID=Europe/Athens:20220915T080000",
+                "DTSTART;TZID=Europe/Athens:20220915T080000",
+                "RRULE:FREQ=WEEKLY;BYDAY=TH",
+                "PRIORITY:1",
+                "CATEGORIES:work",
+                "END:VTODO",
+                "BEGIN:VTODO",
+                "UID:2@doist",
+                "DTSTAMP:20220826T193320Z",
+                "SUMMARY:Get some data",
+                "URL:http://localhost/",
+                "DUE;VALUE=DATE:20220920",
+                "END:VTODO",
+                "END:VCALENDAR",
+                "",
+            ])
+            .collect::<Vec<_>>()
+            .join("\r\n")
+        );
+        let events = calendar(&tasks[1..2], true, &now);
+        assert!(events.contains("BEGIN:VEVENT\r\n"));
+        assert!(events.contains("DTSTART;VALUE=DATE:20220920\r\nDTEND;VALUE=DATE:20220921\r\n"));
+    }
+}
@@ -0,0 +1,383 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use color_eyre::{Result, eyre::WrapErr};
+
+use crate::{
+    api::rest::{
+        CreateLabel, CreateProject, CreateTask, Gateway, Label, Priority, Project, ProjectID, Task,
+        TaskDue,
+    },
+    config::Config,
+};
+
+use super::{taskwarrior, todotxt};
+
+/// The formats of other todo list tools that tasks can be exchanged with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Todotxt,
+    Taskwarrior,
+}
+
+/// A task in a form that all interchange formats can be converted to and from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Entry {
+    pub content: String,
+    pub description: Option<String>,
+    pub priority: Priority,
+    /// Names of the project and all its parents, starting with the top level project.
+    pub project: Vec<String>,
+    pub labels: Vec<String>,
+    pub due: Option<EntryDue>,
+    pub completed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EntryDue {
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct ExportParams {
+    /// File to write the tasks to. Writes to stdout if omitted.
+    file: Option<PathBuf>,
+    /// Only exports tasks passing this filter.
+    #[arg(short = 'f', long = "filter", default_value = "all")]
+    filter: String,
+}
+
+#[derive(clap::Parser, Debug)]
+pub struct ImportParams {
+    /// File to read the tasks from. Use `-` to read from stdin.
+    file: PathBuf,
+    /// Only shows what would be created without changing anything.
+    #[arg(long = "dry-run")]
+    dry_run: bool,
+}
+
+/// Exports tasks in the format of another todo list tool.
+pub async fn export(
+    params: ExportParams,
+    format: Format,
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<()> {
+    let (projects, mut tasks) = tokio::try_join!(gw.projects(), gw.tasks(Some(&params.filter)))?;
+    tasks.sort();
+    let projects: HashMap<_, _> = projects.into_iter().map(|p| (p.id.clone(), p)).collect();
+    let entries = tasks
+        .iter()
+        .map(|t| entry(t, &projects))
+        .collect::<Vec<_>>();
+    let text = match format {
+        Format::Todotxt => todotxt::render(&entries),
+        Format::Taskwarrior => {
+            taskwarrior::render(&entries, &cfg.override_time.unwrap_or_else(Utc::now))?
+        }
+    };
+    match params.file {
+        Some(file) => {
+            fs::write(&file, text)
+                .wrap_err_with(|| format!("unable to write {}", file.display()))?;
+            println!("exported {} tasks to {}", entries.len(), file.display());
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+/// Converts a task into an entry, resolving the names of its project and parent projects.
+fn entry(task: &Task, projects: &HashMap<ProjectID, Project>) -> Entry {
+    let mut path = Vec::new();
+    let mut project = projects.get(&task.project_id);
+    while let Some(p) = project {
+        // Tasks without a project end up in the inbox again when importing.
+        if p.inbox_project {
+            break;
+        }
+        path.insert(0, p.name.clone());
+        project = p.parent_id.as_ref().and_then(|id| projects.get(id));
+    }
+    Entry {
+        content: task.content.clone(),
+        description: (!task.description.is_empty()).then(|| task.description.clone()),
+        priority: task.priority,
+        project: path,
+        labels: task.labels.clone(),
+        due: task.due.as_ref().map(|due| match &due.exact {
+            Some(exact) => EntryDue::DateTime(exact.datetime.with_timezone(&Utc)),
+            None => EntryDue::Date(due.date),
+        }),
+        completed: false,
+    }
+}
+
+/// Imports tasks from another todo list tool, creating missing projects and labels.
+pub async fn import(params: ImportParams, format: Format, gw: &Gateway) -> Result<()> {
+    let text = if params.file.as_os_str() == "-" {
+        std::io::read_to_string(std::io::stdin()).wrap_err("unable to read stdin")?
+    } else {
+        fs::read_to_string(&params.file)
+            .wrap_err_with(|| format!("unable to read {}", params.file.display()))?
+    };
+    let entries = match format {
+        Format::Todotxt => todotxt::parse(&text),
+        Format::Taskwarrior => taskwarrior::parse(&text)?,
+    };
+    let (projects, labels) = tokio::try_join!(gw.projects(), gw.labels())?;
+    let plan = Plan::new(entries, &projects, &labels);
+    if params.dry_run {
+        plan.print();
+        return Ok(());
+    }
+
+    let mut project_ids = project_paths(&projects)
+        .into_iter()
+        .map(|(path, project)| (path, project.id.clone()))
+        .collect::<HashMap<_, _>>();
+    for path in &plan.projects {
+        let parent_id = project_ids
+            .get(&normalize(&path[..path.len() - 1]))
+            .cloned();
+        let project = gw
+            .create_project(&CreateProject {
+                name: path.last().unwrap().clone(),
+                parent_id,
+                ..Default::default()
+            })
+            .await?;
+        project_ids.insert(normalize(path), project.id);
+    }
+    let mut label_names = labels
+        .into_iter()
+        .map(|l| (l.name.to_lowercase(), l.name))
+        .collect::<HashMap<_, _>>();
+    for name in &plan.labels {
+        let label = gw
+            .create_label(&CreateLabel {
+                name: name.clone(),
+                ..Default::default()
+            })
+            .await?;
+        label_names.insert(label.name.to_lowercase(), label.name);
+    }
+    for entry in &plan.tasks {
+        gw.create(&CreateTask {
+            content: entry.content.clone(),
+            description: entry.description.clone(),
+            project_id: project_ids.get(&normalize(&entry.project)).cloned(),
+            labels: entry
+                .labels
+                .iter()
+                .map(|l| {
+                    label_names
+                        .get(&l.to_lowercase())
+                        .cloned()
+                        .unwrap_or_else(|| l.clone())
+                })
+                .collect(),
+            priority: Some(entry.priority),
+            due: entry.due.as_ref().map(|due| match due {
+                EntryDue::Date(date) => TaskDue::Date(date.format("%Y-%m-%d").to_string()),
+                EntryDue::DateTime(datetime) => TaskDue::DateTime(*datetime),
+            }),
+            ..Default::default()
+        })
+        .await?;
+    }
+    println!(
+        "imported {} tasks, created {} projects and {} labels",
+        plan.tasks.len(),
+        plan.projects.len(),
+        plan.labels.len()
+    );
+    if plan.skipped > 0 {
+        println!("skipped {} completed tasks", plan.skipped);
+    }
+    Ok(())
+}
+
+/// Lowercases the project path so that names can be compared regardless of their case.
+fn normalize(path: &[String]) -> Vec<String> {
+    path.iter().map(|p| p.to_lowercase()).collect()
+}
+
+/// Returns the normalized path of names from the top level project for each project.
+///
+/// Nested projects can also be found by their name alone unless a top level project has the same
+/// name, as todo.txt doesn't know about the project hierarchy.
+fn project_paths(projects: &[Project]) -> HashMap<Vec<String>, &Project> {
+    let by_id: HashMap<_, _> = projects.iter().map(|p| (&p.id, p)).collect();
+    let mut paths: HashMap<_, _> = projects
+        .iter()
+        .map(|project| {
+            let mut path = Vec::new();
+            let mut current = Some(project);
+            while let Some(p) = current {
+                path.insert(0, p.name.clone());
+                current = p.parent_id.as_ref().and_then(|id| by_id.get(id).copied());
+            }
+            (normalize(&path), project)
+        })
+        .collect();
+    for project in projects {
+        paths
+            .entry(normalize(std::slice::from_ref(&project.name)))
+            .or_insert(project);
+    }
+    paths
+}
+
+/// Describes everything that an import is going to create.
+#[derive(Debug, PartialEq)]
+struct Plan {
+    /// Projects that don't exist yet, with parents coming before their children.
+    projects: Vec<Vec<String>>,
+    /// Labels that don't exist yet.
+    labels: Vec<String>,
+    /// Tasks that will be created.
+    tasks: Vec<Entry>,
+    /// Number of completed tasks that are not imported.
+    skipped: usize,
+}
+
+impl Plan {
+    fn new(entries: Vec<Entry>, projects: &[Project], labels: &[Label]) -> Plan {
+        let mut existing_projects = project_paths(projects).into_keys().collect::<HashSet<_>>();
+        let mut existing_labels = labels
+            .iter()
+            .map(|l| l.name.to_lowercase())
+            .collect::<HashSet<_>>();
+        let (completed, tasks): (Vec<_>, Vec<_>) = entries.into_iter().partition(|e| e.completed);
+        let mut plan = Plan {
+            projects: Vec::new(),
+            labels: Vec::new(),
+            tasks,
+            skipped: completed.len(),
+        };
+        for entry in &plan.tasks {
+            for i in 1..=entry.project.len() {
+                if existing_projects.insert(normalize(&entry.project[..i])) {
+                    plan.projects.push(entry.project[..i].to_vec());
+                }
+            }
+            for label in &entry.labels {
+                if existing_labels.insert(label.to_lowercase()) {
+                    plan.labels.push(label.clone());
+                }
+            }
+        }
+        plan
+    }
+
+    fn print(&self) {
+        for project in &self.projects {
+            println!("would create project {}", project.join("/"));
+        }
+        for label in &self.labels {
+            println!("would create label @{label}");
+        }
+        println!("would import {} tasks:", self.tasks.len());
+        for task in &self.tasks {
+            println!(
+                "  {}",
+                todotxt::render(std::slice::from_ref(task)).trim_end()
+            );
+        }
+        if self.skipped > 0 {
+            println!("would skip {} completed tasks", self.skipped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn plans_missing_projects_and_labels() {
+        let projects = vec![
+            Project::new("1", "Work"),
+            Project {
+                parent_id: Some("1".to_string()),
+                ..Project::new("2", "Q3")
+            },
+        ];
+        let labels = vec![Label::new("1", "Deep")];
+        let entries = vec![
+            Entry {
+                content: "one".to_string(),
+                project: vec!["work".to_string(), "Q3".to_string()],
+                labels: vec!["deep".to_string(), "quick".to_string()],
+                ..Default::default()
+            },
+            Entry {
+                content: "two".to_string(),
+                project: vec!["Work".to_string(), "Q4".to_string(), "Launch".to_string()],
+                labels: vec!["Quick".to_string()],
+                ..Default::default()
+            },
+            Entry {
+                content: "nested by name".to_string(),
+                project: vec!["q3".to_string()],
+                ..Default::default()
+            },
+            Entry {
+                content: "three".to_string(),
+                project: vec!["Home".to_string()],
+                completed: true,
+                ..Default::default()
+            },
+        ];
+        let plan = Plan::new(entries.clone(), &projects, &labels);
+        assert_eq!(
+            plan,
+            Plan {
+                projects: vec![
+                    vec!["Work".to_string(), "Q4".to_string()],
+                    vec!["Work".to_string(), "Q4".to_string(), "Launch".to_string()],
+                ],
+                labels: vec!["quick".to_string()],
+                tasks: entries[..3].to_vec(),
+                skipped: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn converts_tasks() {
+        let projects = HashMap::from([
+            (
+                "1".to_string(),
+                Project {
+                    inbox_project: true,
+                    ..Project::new("1", "Inbox")
+                },
+            ),
+            ("2".to_string(), Project::new("2", "Work")),
+            (
+                "3".to_string(),
+                Project {
+                    parent_id: Some("2".to_string()),
+                    ..Project::new("3", "Q3")
+                },
+            ),
+        ]);
+        let task = Task {
+            project_id: "3".to_string(),
+            ..Task::new("1", "one")
+        };
+        assert_eq!(entry(&task, &projects).project, vec!["Work", "Q3"]);
+        let task = Task {
+            project_id: "1".to_string(),
+            ..Task::new("1", "one")
+        };
+        assert!(entry(&task, &projects).project.is_empty());
+    }
+}
@@ -0,0 +1,6 @@
+//! Exports tasks into formats that other tools understand, and imports them back.
+pub mod ics;
+pub mod interchange;
+pub mod outline;
+mod taskwarrior;
+mod todotxt;
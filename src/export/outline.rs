@@ -0,0 +1,386 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use tokio::task::JoinSet;
+
+use crate::{
+    api::{
+        rest::{Comment, DueDate, Gateway, Priority, Project, ProjectID, Section, Task},
+        tree::{Tree, TreeFlattenExt},
+    },
+    config::Config,
+    interactive,
+    projects::state::State,
+};
+
+/// How many comment requests are sent to the Todoist API at the same time.
+const CONCURRENCY: usize = 4;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    #[clap(flatten)]
+    project: interactive::Selection<Project>,
+    /// File to write the document to. Writes to stdout if omitted.
+    file: Option<PathBuf>,
+    /// Includes the comments of the project and its tasks.
+    #[arg(short = 'c', long = "comments")]
+    comments: bool,
+}
+
+/// The document format that a project is exported as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Markdown,
+    Org,
+}
+
+/// Exports a project with its subprojects, sections and tasks as a document of nested checklists.
+pub async fn export(params: Params, format: Format, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let projects = gw.projects().await?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
+    let (state, tasks) = tokio::try_join!(State::fetch_tree(gw), gw.tasks(None))?;
+    let tree = state
+        .project(&project.id)
+        .ok_or_else(|| eyre!("full project list contained invalid data"))?;
+    let project_ids = vec![tree.clone()]
+        .flat_tree()
+        .into_iter()
+        .map(|p| p.id.clone())
+        .collect::<Vec<_>>();
+    let tasks = tasks
+        .into_iter()
+        .filter(|t| project_ids.contains(&t.project_id))
+        .collect::<Vec<_>>();
+    let comments = if params.comments {
+        comments(&project_ids, &tasks, gw).await?
+    } else {
+        HashMap::new()
+    };
+    let tasks = Tree::from_items(tasks).wrap_err("tasks do not form a clean tree")?;
+    let document = Document {
+        format,
+        sections: project_ids
+            .iter()
+            .flat_map(|id| state.sections(id))
+            .collect(),
+        tasks: &tasks,
+        comments: &comments,
+    }
+    .render(tree);
+    match params.file {
+        Some(file) => {
+            fs::write(&file, document)
+                .wrap_err_with(|| format!("unable to write {}", file.display()))?;
+            println!("exported {} to {}", tree.name, file.display());
+        }
+        None => print!("{document}"),
+    }
+    Ok(())
+}
+
+/// Fetches the comments of the projects and of the tasks that have any, with at most
+/// [`CONCURRENCY`] requests at a time. The comments are keyed by the ID of their project or task.
+async fn comments(
+    projects: &[ProjectID],
+    tasks: &[Task],
+    gw: &Gateway,
+) -> Result<HashMap<String, Vec<Comment>>> {
+    let mut pending = projects.iter().map(|id| (id.clone(), true)).chain(
+        tasks
+            .iter()
+            .filter(|t| t.note_count > 0)
+            .map(|t| (t.id.clone(), false)),
+    );
+    let mut running = JoinSet::new();
+    let mut comments = HashMap::new();
+    loop {
+        while running.len() < CONCURRENCY
+            && let Some((id, project)) = pending.next()
+        {
+            let gw = gw.clone();
+            running.spawn(async move {
+                let result = if project {
+                    gw.project_comments(&id).await
+                } else {
+                    gw.task_comments(&id).await
+                };
+                (id, result)
+            });
+        }
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (id, result) = joined.wrap_err("fetching comments was aborted")?;
+        let result = result?;
+        if !result.is_empty() {
+            comments.insert(id, result);
+        }
+    }
+    Ok(comments)
+}
+
+/// Holds everything that is needed to render a project tree into a document.
+struct Document<'a> {
+    format: Format,
+    sections: Vec<&'a Section>,
+    tasks: &'a [Tree<Task>],
+    /// Comments of both projects and tasks, keyed by their ID.
+    comments: &'a HashMap<String, Vec<Comment>>,
+}
+
+impl Document<'_> {
+    fn render(&self, project: &Tree<Project>) -> String {
+        let mut out = String::new();
+        self.project(&mut out, project, 1);
+        out
+    }
+
+    fn project(&self, out: &mut String, project: &Tree<Project>, level: usize) {
+        self.heading(out, level, &project.name);
+        if self.comments.contains_key(&project.id) {
+            out.push('\n');
+            self.comments(out, &project.id, 0);
+        }
+        self.tasks(out, &project.id, None);
+        let mut sections = self
+            .sections
+            .iter()
+            .filter(|s| s.project_id == project.id)
+            .collect::<Vec<_>>();
+        sections.sort();
+        for section in sections {
+            self.heading(out, level + 1, &section.name);
+            self.tasks(out, &project.id, Some(&section.id));
+        }
+        for subproject in &project.subitems {
+            self.project(out, subproject, level + 1);
+        }
+    }
+
+    fn heading(&self, out: &mut String, level: usize, text: &str) {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        let marker = match self.format {
+            Format::Markdown => "#",
+            Format::Org => "*",
+        };
+        out.push_str(&format!("{} {}\n", marker.repeat(level), text));
+    }
+
+    fn tasks(&self, out: &mut String, project: &ProjectID, section: Option<&String>) {
+        let mut tasks = self
+            .tasks
+            .iter()
+            .filter(|t| t.project_id == *project && t.section_id.as_ref() == section)
+            .collect::<Vec<_>>();
+        if tasks.is_empty() {
+            return;
+        }
+        tasks.sort();
+        out.push('\n');
+        for task in tasks {
+            self.task(out, task, 0);
+        }
+    }
+
+    fn task(&self, out: &mut String, task: &Tree<Task>, indent: usize) {
+        let pad = "  ".repeat(indent);
+        let mut line = format!("{pad}- [ ] {}", task.content);
+        let mut details = Vec::new();
+        if task.priority != Priority::Normal {
+            details.push(task.priority.label().to_string());
+        }
+        if let Some(due) = &task.due {
+            details.push(self.due(due));
+        }
+        details.extend(task.labels.iter().map(|l| format!("@{l}")));
+        if !details.is_empty() {
+            line.push_str(&format!(" ({})", details.join(", ")));
+        }
+        out.push_str(&line);
+        out.push('\n');
+        for description in task.description.lines() {
+            out.push_str(&format!("{pad}  {description}\n"));
+        }
+        self.comments(out, &task.id, indent + 1);
+        let mut subtasks = task.subitems.iter().collect::<Vec<_>>();
+        subtasks.sort();
+        for subtask in subtasks {
+            self.task(out, subtask, indent + 1);
+        }
+    }
+
+    fn due(&self, due: &DueDate) -> String {
+        let (date_format, time_format) = match self.format {
+            Format::Markdown => ("%Y-%m-%d", "%Y-%m-%d %H:%M"),
+            Format::Org => ("<%Y-%m-%d %a>", "<%Y-%m-%d %a %H:%M>"),
+        };
+        let mut text = match &due.exact {
+            Some(exact) => match exact.timezone.parse::<chrono_tz::Tz>() {
+                Ok(tz) => exact
+                    .datetime
+                    .with_timezone(&tz)
+                    .format(time_format)
+                    .to_string(),
+                Err(_) => exact.datetime.format(time_format).to_string(),
+            },
+            None => due.date.format(date_format).to_string(),
+        };
+        if due.is_recurring {
+            text.push_str(&format!(" {}", due.string));
+        }
+        format!("due {text}")
+    }
+
+    fn comments(&self, out: &mut String, id: &str, indent: usize) {
+        let comments = match self.comments.get(id) {
+            Some(comments) => comments,
+            None => return,
+        };
+        let pad = "  ".repeat(indent);
+        match self.format {
+            Format::Markdown => {
+                for comment in comments {
+                    for line in comment.content.lines() {
+                        out.push_str(&format!("{pad}> {line}\n"));
+                    }
+                }
+            }
+            Format::Org => {
+                out.push_str(&format!("{pad}#+begin_quote\n"));
+                for comment in comments {
+                    for line in comment.content.lines() {
+                        out.push_str(&format!("{pad}{line}\n"));
+                    }
+                }
+                out.push_str(&format!("{pad}#+end_quote\n"));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::api::rest::ExactTime;
+
+    fn render(format: Format) -> String {
+        let mut project = Tree::new(Project::new("1", "Work"));
+        let mut subproject = Tree::new(Project {
+            parent_id: Some("1".to_string()),
+            ..Project::new("2", "Q3")
+        });
+        subproject.depth = 1;
+        project.subitems.push(subproject);
+        let section = Section::new("3", "1", "Meetings");
+        let tasks = Tree::from_items(vec![
+            Task {
+                project_id: "1".to_string(),
+                priority: Priority::Urgent,
+                labels: vec!["deep".to_string()],
+                description: "Needs focus".to_string(),
+                ..Task::new("10", "Write plan")
+            },
+            Task {
+                project_id: "1".to_string(),
+                parent_id: Some("10".to_string()),
+                due: Some(DueDate {
+                    string: "20 Sep".to_string(),
+                    date: "2022-09-20".parse().unwrap(),
+                    is_recurring: false,
+                    exact: None,
+                }),
+                ..Task::new("11", "Outline")
+            },
+            Task {
+                project_id: "1".to_string(),
+                section_id: Some("3".to_string()),
+                due: Some(DueDate {
+                    string: "every thursday 8am".to_string(),
+                    date: "2022-09-15".parse().unwrap(),
+                    is_recurring: true,
+                    exact: Some(ExactTime {
+                        datetime: "2022-09-15T05:00:00Z".parse().unwrap(),
+                        timezone: "Europe/Athens".to_string(),
+                    }),
+                }),
+                ..Task::new("12", "Standup")
+            },
+            Task {
+                project_id: "2".to_string(),
+                ..Task::new("13", "Ship it")
+            },
+        ])
+        .unwrap();
+        let comments = HashMap::from([(
+            "12".to_string(),
+            vec![Comment {
+                id: "1".to_string(),
+                item_id: Some("12".to_string()),
+                project_id: None,
+                posted_at: "2022-08-26T19:33:20Z".parse().unwrap(),
+                content: "Bring notes".to_string(),
+                attachment: None,
+            }],
+        )]);
+        Document {
+            format,
+            sections: vec![&section],
+            tasks: &tasks,
+            comments: &comments,
+        }
+        .render(&project)
+    }
+
+    #[test]
+    fn renders_markdown() {
+        assert_eq!(
+            render(Format::Markdown),
+            "# Work
+
+- [ ] Write plan (p1, @deep)
+  Needs focus
+  - [ ] Outline (due 2022-09-20)
+
+## Meetings
+
+- [ ] Standup (due 2022-09-15 08:00 every thursday 8am)
+  > Bring notes
+
+## Q3
+
+- [ ] Ship it
+"
+        );
+    }
+
+    #[test]
+    fn renders_org() {
+        assert_eq!(
+            render(Format::Org),
+            "* Work
+
+- [ ] Write plan (p1, @deep)
+  Needs focus
+  - [ ] Outline (due <2022-09-20 Tue>)
+
+** Meetings
+
+- [ ] Standup (due <2022-09-15 Thu 08:00> every thursday 8am)
+  #+begin_quote
+  Bring notes
+  #+end_quote
+
+** Q3
+
+- [ ] Ship it
+"
+        );
+    }
+}
@@ -0,0 +1,219 @@
+//! Reads and writes the JSON format of [Taskwarrior](https://taskwarrior.org/)'s `task export`
+//! and `task import`.
+//!
+//! Nested projects are separated by dots, like `Work.Q3`.
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use color_eyre::{Result, eyre::WrapErr};
+use serde::{Deserialize, Serialize};
+
+use crate::api::rest::Priority;
+
+use super::interchange::{Entry, EntryDue};
+
+const DATE_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct TwTask {
+    description: String,
+    #[serde(default = "pending")]
+    status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    priority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    due: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    annotations: Vec<Annotation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct Annotation {
+    entry: String,
+    description: String,
+}
+
+fn pending() -> String {
+    "pending".to_string()
+}
+
+/// Renders entries as a JSON array that `task import` understands. Descriptions are written as
+/// annotations made at the given time.
+pub fn render(entries: &[Entry], now: &DateTime<Utc>) -> Result<String> {
+    let tasks = entries
+        .iter()
+        .map(|entry| TwTask {
+            description: entry.content.clone(),
+            status: pending(),
+            project: (!entry.project.is_empty()).then(|| entry.project.join(".")),
+            tags: entry.labels.clone(),
+            priority: match entry.priority {
+                Priority::Urgent => Some("H".to_string()),
+                Priority::VeryHigh => Some("M".to_string()),
+                Priority::High => Some("L".to_string()),
+                Priority::Normal => None,
+            },
+            due: entry.due.as_ref().map(|due| {
+                match due {
+                    // Taskwarrior stores dates without time as local midnight.
+                    EntryDue::Date(date) => Local
+                        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                        .earliest()
+                        .map(|d| d.with_timezone(&Utc))
+                        .unwrap_or_else(|| date.and_time(NaiveTime::MIN).and_utc()),
+                    EntryDue::DateTime(datetime) => *datetime,
+                }
+                .format(DATE_FORMAT)
+                .to_string()
+            }),
+            annotations: entry
+                .description
+                .iter()
+                .map(|d| Annotation {
+                    entry: now.format(DATE_FORMAT).to_string(),
+                    description: d.clone(),
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+    Ok(format!("{}\n", serde_json::to_string_pretty(&tasks)?))
+}
+
+/// Parses the output of `task export`, which is either a JSON array or one task per line.
+/// Completed and deleted tasks are marked as completed. Templates of recurring tasks are skipped,
+/// as their instances are exported as tasks of their own.
+pub fn parse(text: &str) -> Result<Vec<Entry>> {
+    let tasks: Vec<TwTask> = if text.trim_start().starts_with('[') {
+        serde_json::from_str(text).wrap_err("invalid Taskwarrior JSON")?
+    } else {
+        text.lines()
+            .filter(|l| !l.trim().is_empty())
+            .map(|l| serde_json::from_str(l.trim_end_matches(',')))
+            .collect::<std::result::Result<_, _>>()
+            .wrap_err("invalid Taskwarrior JSON")?
+    };
+    tasks
+        .into_iter()
+        .filter(|t| t.status != "recurring")
+        .map(entry)
+        .collect()
+}
+
+fn entry(task: TwTask) -> Result<Entry> {
+    let due = match task.due {
+        Some(due) => {
+            let datetime = NaiveDateTime::parse_from_str(&due, DATE_FORMAT)
+                .wrap_err_with(|| format!("invalid due date '{due}'"))?
+                .and_utc();
+            let local = datetime.with_timezone(&Local);
+            Some(if local.time() == NaiveTime::MIN {
+                EntryDue::Date(local.date_naive())
+            } else {
+                EntryDue::DateTime(datetime)
+            })
+        }
+        None => None,
+    };
+    let description = task
+        .annotations
+        .into_iter()
+        .map(|a| a.description)
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(Entry {
+        content: task.description,
+        description: (!description.is_empty()).then_some(description),
+        priority: match task.priority.as_deref() {
+            Some("H") => Priority::Urgent,
+            Some("M") => Priority::VeryHigh,
+            Some("L") => Priority::High,
+            _ => Priority::Normal,
+        },
+        project: task
+            .project
+            .map(|p| p.split('.').map(str::to_string).collect())
+            .unwrap_or_default(),
+        labels: task.tags,
+        due,
+        completed: matches!(task.status.as_str(), "completed" | "deleted"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_export() {
+        let entries = parse(
+            r#"[
+                {"id":1,"description":"Write plan","status":"pending","project":"Work.Q3",
+                 "tags":["deep"],"priority":"H","due":"20220920T133700Z",
+                 "annotations":[{"entry":"20220826T193320Z","description":"Needs focus"}],
+                 "uuid":"9f4c1b1e-1111-2222-3333-444455556666","urgency":12.3},
+                {"description":"Old","status":"completed"},
+                {"description":"Standup","status":"recurring","recur":"daily"},
+                {"description":"Standup","status":"pending","recur":"daily"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    content: "Write plan".to_string(),
+                    description: Some("Needs focus".to_string()),
+                    priority: Priority::Urgent,
+                    project: vec!["Work".to_string(), "Q3".to_string()],
+                    labels: vec!["deep".to_string()],
+                    due: Some(EntryDue::DateTime("2022-09-20T13:37:00Z".parse().unwrap())),
+                    completed: false,
+                },
+                Entry {
+                    content: "Old".to_string(),
+                    completed: true,
+                    ..Default::default()
+                },
+                Entry {
+                    content: "Standup".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+        assert_eq!(
+            parse("{\"description\":\"a\"}\n{\"description\":\"b\"}\n")
+                .unwrap()
+                .len(),
+            2
+        );
+        assert!(parse("[{\"nope\":1}]").is_err());
+    }
+
+    #[test]
+    fn round_trips() {
+        let now = "2022-08-26T19:33:20Z".parse().unwrap();
+        let entries = vec![
+            Entry {
+                content: "Write plan".to_string(),
+                description: Some("Needs focus".to_string()),
+                priority: Priority::High,
+                project: vec!["Work".to_string(), "Q3".to_string()],
+                labels: vec!["deep".to_string()],
+                due: Some(EntryDue::Date("2022-09-20".parse().unwrap())),
+                completed: false,
+            },
+            Entry {
+                content: "Call".to_string(),
+                due: Some(EntryDue::DateTime("2022-09-20T13:37:00Z".parse().unwrap())),
+                ..Default::default()
+            },
+        ];
+        let rendered = render(&entries, &now).unwrap();
+        assert!(rendered.contains("\"project\": \"Work.Q3\""));
+        assert!(rendered.contains("\"priority\": \"L\""));
+        assert!(rendered.contains("\"due\": \"20220920T133700Z\""));
+        assert_eq!(parse(&rendered).unwrap(), entries);
+    }
+}
@@ -0,0 +1,170 @@
+//! Reads and writes the [todo.txt](https://github.com/todotxt/todo.txt) format.
+//!
+//! Projects and contexts can't contain spaces in todo.txt, so spaces are written as underscores
+//! and underscores in project and label names are read back as spaces.
+use chrono::{Local, NaiveDate};
+
+use crate::api::rest::Priority;
+
+use super::interchange::{Entry, EntryDue};
+
+/// Renders entries as todo.txt lines.
+pub fn render(entries: &[Entry]) -> String {
+    entries.iter().map(|e| format!("{}\n", line(e))).collect()
+}
+
+fn line(entry: &Entry) -> String {
+    let mut parts = Vec::new();
+    match entry.priority {
+        Priority::Urgent => parts.push("(A)".to_string()),
+        Priority::VeryHigh => parts.push("(B)".to_string()),
+        Priority::High => parts.push("(C)".to_string()),
+        Priority::Normal => {}
+    }
+    parts.push(entry.content.clone());
+    if let Some(project) = entry.project.last() {
+        parts.push(format!("+{}", project.replace(' ', "_")));
+    }
+    parts.extend(
+        entry
+            .labels
+            .iter()
+            .map(|l| format!("@{}", l.replace(' ', "_"))),
+    );
+    if let Some(due) = &entry.due {
+        let date = match due {
+            EntryDue::Date(date) => *date,
+            EntryDue::DateTime(datetime) => datetime.with_timezone(&Local).date_naive(),
+        };
+        parts.push(format!("due:{}", date.format("%Y-%m-%d")));
+    }
+    parts.join(" ")
+}
+
+/// Parses todo.txt lines into entries. Completed tasks are kept, but marked as such.
+pub fn parse(text: &str) -> Vec<Entry> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(parse_line)
+        .collect()
+}
+
+fn parse_line(line: &str) -> Entry {
+    let mut words = line.split_whitespace().peekable();
+    let mut entry = Entry::default();
+    if words.peek() == Some(&"x") {
+        entry.completed = true;
+        words.next();
+    }
+    if let Some(priority) = words.peek().and_then(|w| priority(w)) {
+        entry.priority = priority;
+        words.next();
+    }
+    // Completion and creation dates come before the content.
+    while words
+        .peek()
+        .is_some_and(|w| NaiveDate::parse_from_str(w, "%Y-%m-%d").is_ok())
+    {
+        words.next();
+    }
+    let mut content = Vec::new();
+    for word in words {
+        if let Some(project) = word.strip_prefix('+').filter(|p| !p.is_empty())
+            && entry.project.is_empty()
+        {
+            entry.project = vec![project.replace('_', " ")];
+        } else if let Some(label) = word.strip_prefix('@').filter(|l| !l.is_empty()) {
+            entry.labels.push(label.replace('_', " "));
+        } else if let Some(date) = word
+            .strip_prefix("due:")
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        {
+            entry.due = Some(EntryDue::Date(date));
+        } else if word.starts_with("pri:") && entry.completed {
+            // Some clients keep the priority of completed tasks around, which we don't need.
+        } else {
+            content.push(word);
+        }
+    }
+    entry.content = content.join(" ");
+    entry
+}
+
+fn priority(word: &str) -> Option<Priority> {
+    Some(match word {
+        "(A)" => Priority::Urgent,
+        "(B)" => Priority::VeryHigh,
+        "(C)" => Priority::High,
+        w if w.len() == 3
+            && w.starts_with('(')
+            && w.ends_with(')')
+            && w.as_bytes()[1].is_ascii_uppercase() =>
+        {
+            Priority::Normal
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_lines() {
+        let entries = parse(
+            "(A) 2022-08-01 Call mom +Family_Stuff @phone due:2022-09-20 extra:tag\n\
+             \n\
+             x 2022-08-02 2022-08-01 Done already +Work\n\
+             (D) Low +one +two\n",
+        );
+        assert_eq!(
+            entries,
+            vec![
+                Entry {
+                    content: "Call mom extra:tag".to_string(),
+                    priority: Priority::Urgent,
+                    project: vec!["Family Stuff".to_string()],
+                    labels: vec!["phone".to_string()],
+                    due: Some(EntryDue::Date("2022-09-20".parse().unwrap())),
+                    ..Default::default()
+                },
+                Entry {
+                    content: "Done already".to_string(),
+                    project: vec!["Work".to_string()],
+                    completed: true,
+                    ..Default::default()
+                },
+                Entry {
+                    content: "Low +two".to_string(),
+                    project: vec!["one".to_string()],
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_lines() {
+        let entry = Entry {
+            content: "Call mom".to_string(),
+            priority: Priority::VeryHigh,
+            project: vec!["Personal".to_string(), "Family Stuff".to_string()],
+            labels: vec!["phone".to_string(), "call back".to_string()],
+            due: Some(EntryDue::Date("2022-09-20".parse().unwrap())),
+            ..Default::default()
+        };
+        assert_eq!(
+            render(std::slice::from_ref(&entry)),
+            "(B) Call mom +Family_Stuff @phone @call_back due:2022-09-20\n"
+        );
+        assert_eq!(
+            parse(&render(std::slice::from_ref(&entry))),
+            vec![Entry {
+                project: vec!["Family Stuff".to_string()],
+                ..entry
+            }]
+        );
+    }
+}
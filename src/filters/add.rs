@@ -0,0 +1,35 @@
+use crate::{
+    api::rest::{CreateFilter, Gateway},
+    config::Config,
+    tasks::filter,
+};
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Name of the filter to create.
+    name: String,
+    /// The filter query, like `(today | overdue) & #Work`.
+    query: String,
+}
+
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    filter::check(&params.query)?;
+    let id = gw
+        .create_filter(&CreateFilter {
+            name: params.name,
+            query: params.query,
+            ..Default::default()
+        })
+        .await?;
+    let filters = gw.filters().await?;
+    let filter = filters
+        .iter()
+        .find(|f| f.id == id)
+        .ok_or_else(|| eyre!("unable to find created filter"))?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(filter);
+    }
+    println!("created filter: {filter}");
+    Ok(())
+}
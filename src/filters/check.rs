@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use color_eyre::{Result, eyre::eyre};
+use serde::Serialize;
+
+use crate::{
+    api::rest::Gateway,
+    config::Config,
+    interactive::{self, FuzzSelect},
+    query::{self, Term},
+    tasks::filter,
+};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The filter query to check, like `(today | overdue) & #Work`.
+    query: String,
+}
+
+/// A part of the query that would not match anything.
+#[derive(Serialize)]
+struct Problem {
+    message: String,
+    span: Range<usize>,
+}
+
+/// Checks the syntax of the query and that the projects, sections and labels in it exist.
+pub async fn check(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let terms = filter::check(&params.query)?;
+    let (projects, sections, labels) = tokio::try_join!(gw.projects(), gw.sections(), gw.labels())?;
+    let mut problems = Vec::new();
+    let mut notes = Vec::new();
+    for (term, span) in terms {
+        let message = match &term {
+            Term::Project { name, .. } => unknown("project", name, &projects),
+            Term::Section(name) => unknown("section", name, &sections),
+            Term::Label(name) => unknown("label", name, &labels),
+            Term::Unknown(text) => {
+                notes.push((
+                    format!(
+                        "`{text}` is left to the Todoist API and can't be used with --local-filter"
+                    ),
+                    span.clone(),
+                ));
+                None
+            }
+            _ => None,
+        };
+        if let Some(message) = message {
+            problems.push(Problem { message, span });
+        }
+    }
+    if !cfg.output.is_text() {
+        cfg.output.list(&problems)?;
+    } else {
+        for (message, span) in &notes {
+            println!("{}", query::annotate(&params.query, span, message));
+        }
+        for problem in &problems {
+            println!(
+                "{}",
+                query::annotate(&params.query, &problem.span, &problem.message)
+            );
+        }
+    }
+    match problems.len() {
+        0 => {
+            if cfg.output.is_text() {
+                println!("filter is valid");
+            }
+            Ok(())
+        }
+        1 => Err(eyre!("filter has 1 problem")),
+        count => Err(eyre!("filter has {count} problems")),
+    }
+}
+
+/// Describes the problem if no item matches the name, suggesting the closest one.
+fn unknown<T: FuzzSelect>(kind: &str, name: &str, items: &[T]) -> Option<String> {
+    if items.iter().any(|i| query::matches_name(name, i.name())) {
+        return None;
+    }
+    Some(match interactive::best_match(items, name) {
+        Some(item) if !name.contains('*') => {
+            format!("unknown {kind} `{name}`, did you mean `{}`?", item.name())
+        }
+        _ => format!("unknown {kind} `{name}`"),
+    })
+}
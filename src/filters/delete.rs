@@ -0,0 +1,24 @@
+use crate::{api::rest::Gateway, config::Config, interactive::Ambiguity};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Uses the filter with the closest name. If omitted, will interactively select the filter.
+    #[arg(value_name = "FILTER")]
+    filter: Option<String>,
+}
+
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let filters = gw.filters().await?;
+    let filter = super::select(
+        &filters,
+        params.filter.as_deref(),
+        Ambiguity::new(cfg.output.is_text()),
+    )?;
+    gw.delete_filter(&filter.id).await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(filter);
+    }
+    println!("deleted filter: {filter}");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use crate::{
+    api::rest::{Gateway, UpdateFilter},
+    config::Config,
+    interactive::Ambiguity,
+    tasks::filter,
+};
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Uses the filter with the closest name. If omitted, will interactively select the filter.
+    #[arg(value_name = "FILTER")]
+    filter: Option<String>,
+    /// Renames the filter.
+    #[arg(short = 'n', long = "name")]
+    name: Option<String>,
+    /// Changes the filter query.
+    #[arg(short = 'q', long = "query")]
+    query: Option<String>,
+}
+
+pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if params.name.is_none() && params.query.is_none() {
+        return Err(eyre!("no changes given, use --name or --query"));
+    }
+    if let Some(query) = &params.query {
+        filter::check(query)?;
+    }
+    let filters = gw.filters().await?;
+    let mut filter = super::select(
+        &filters,
+        params.filter.as_deref(),
+        Ambiguity::new(cfg.output.is_text()),
+    )?
+    .clone();
+    gw.update_filter(
+        &filter.id,
+        &UpdateFilter {
+            name: params.name.clone(),
+            query: params.query.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    if let Some(name) = params.name {
+        filter.name = name;
+    }
+    if let Some(query) = params.query {
+        filter.query = query;
+    }
+    if !cfg.output.is_text() {
+        return cfg.output.item(&filter);
+    }
+    println!("updated filter: {filter}");
+    Ok(())
+}
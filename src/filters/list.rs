@@ -0,0 +1,16 @@
+use crate::{api::rest::Gateway, config::Config};
+use color_eyre::Result;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {}
+
+pub async fn list(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let filters = gw.filters().await?;
+    if !cfg.output.is_text() {
+        return cfg.output.list(&filters);
+    }
+    for filter in filters {
+        println!("{filter}");
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+//! Controls things that work with saved [`crate::api::rest::Filter`]s and filter queries.
+use color_eyre::{Result, eyre::eyre};
+
+use crate::{
+    api::rest::Filter,
+    interactive::{self, Ambiguity},
+};
+
+pub mod add;
+pub mod check;
+pub mod delete;
+pub mod edit;
+pub mod list;
+
+/// Selects the filter with the closest name, or interactively if no name is given.
+pub fn select<'a>(
+    filters: &'a [Filter],
+    name: Option<&str>,
+    ambiguity: Ambiguity,
+) -> Result<&'a Filter> {
+    if filters.is_empty() {
+        return Err(eyre!("no saved filters found"));
+    }
+    match name {
+        Some(name) => interactive::fuzz_select(filters, name, ambiguity),
+        None => interactive::select("Select filter", filters)?
+            .map(|index| &filters[index])
+            .ok_or_else(|| eyre!("no filter selected")),
+    }
+}
@@ -5,7 +5,8 @@ use owo_colors::OwoColorize;
 use std::iter;
 
 use crate::api::rest::{
-    Label, LabelID, Priority, Project, ProjectID, Section, SectionID, Task, TaskID,
+    Filter, FilterID, Label, LabelID, Priority, Project, ProjectID, Section, SectionID, Task,
+    TaskID,
 };
 use color_eyre::{Result, eyre::WrapErr, eyre::eyre};
 
@@ -104,7 +105,7 @@ selection!(
 );
 
 impl<T: FuzzSelect + std::fmt::Display> Selection<T> {
-    pub fn optional<'a>(&self, items: &'a [T]) -> Result<Option<&'a T>> {
+    pub fn optional<'a>(&self, items: &'a [T], ambiguity: Ambiguity) -> Result<Option<&'a T>> {
         let name = match &self.name {
             Some(name) => name,
             None => {
@@ -114,10 +115,10 @@ impl<T: FuzzSelect + std::fmt::Display> Selection<T> {
                     .and_then(|id| items.iter().find(|item| item.id() == *id)));
             }
         };
-        Ok(Some(fuzz_select(items, name)?))
+        Ok(Some(fuzz_select(items, name, ambiguity)?))
     }
-    pub fn mandatory<'a>(&self, items: &'a [T]) -> Result<&'a T> {
-        let selection = Self::optional(self, items)?;
+    pub fn mandatory<'a>(&self, items: &'a [T], ambiguity: Ambiguity) -> Result<&'a T> {
+        let selection = Self::optional(self, items, ambiguity)?;
         match selection {
             Some(s) => Ok(s),
             None => Ok(select("select item", items)?
@@ -144,17 +145,143 @@ pub fn select<T: ToString>(prompt: &str, items: &[T]) -> Result<Option<usize>> {
     Ok(result)
 }
 
-pub fn fuzz_select<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Result<&'a T> {
+/// Lets the user check any number of items and returns the indices of the checked ones.
+pub fn multi_select<T: ToString>(prompt: &str, items: &[T]) -> Result<Vec<usize>> {
+    let result = dialoguer::MultiSelect::new()
+        .items(items)
+        .with_prompt(prompt)
+        .interact_opt()
+        .wrap_err("Unable to make a selection")?;
+    Ok(result.unwrap_or_default())
+}
+
+/// How close the score of another match has to be to the best match for a name to be ambiguous.
+const AMBIGUOUS_RATIO: f64 = 0.8;
+/// How close the score of the best match has to be to a perfect match to be picked without
+/// asking.
+const MIN_CONFIDENCE: f64 = 0.45;
+/// The maximum number of candidates shown for an ambiguous name.
+const MAX_CANDIDATES: usize = 10;
+
+/// Decides what happens when a name doesn't clearly match a single item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ambiguity {
+    /// Lets the user choose between the candidates.
+    Ask,
+    /// Fails with the list of candidates.
+    Fail,
+}
+
+impl Ambiguity {
+    /// Asks the user if the command is interactive, and fails otherwise.
+    pub fn new(interactive: bool) -> Self {
+        if interactive {
+            Ambiguity::Ask
+        } else {
+            Ambiguity::Fail
+        }
+    }
+}
+
+/// The items whose names match an input.
+pub enum Matches<'a, T> {
+    /// A single item matches clearly.
+    Clear(&'a T),
+    /// No item matches clearly, so one of the candidates has to be chosen.
+    Unclear(Vec<&'a T>),
+}
+
+/// Finds the items whose name matches the input. An item with exactly the same name is always a
+/// clear match, otherwise the best fuzzy match is if no other item matches about as well and it's
+/// close enough to the input.
+pub fn fuzz_match<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Result<Matches<'a, T>> {
     if items.is_empty() {
         return Err(eyre!("no items available for selection, aborting"));
     }
+    let exact = items
+        .iter()
+        .filter(|i| i.name().to_lowercase() == input.to_lowercase())
+        .collect::<Vec<_>>();
+    let candidates = match exact.as_slice() {
+        [item] => return Ok(Matches::Clear(item)),
+        [] => {
+            let matches = fuzzy_matches(items, input);
+            let Some(&(best, item)) = matches.first() else {
+                return Err(eyre!("no suitable item found, aborting"));
+            };
+            let perfect = SkimMatcherV2::default()
+                .fuzzy_match(input, input)
+                .unwrap_or(best);
+            let close = matches
+                .iter()
+                .take_while(|(score, _)| *score as f64 >= best as f64 * AMBIGUOUS_RATIO)
+                .map(|(_, item)| *item)
+                .collect::<Vec<_>>();
+            if close.len() == 1 && best as f64 >= perfect as f64 * MIN_CONFIDENCE {
+                return Ok(Matches::Clear(item));
+            }
+            close
+        }
+        _ => exact,
+    };
+    Ok(Matches::Unclear(
+        candidates.into_iter().take(MAX_CANDIDATES).collect(),
+    ))
+}
+
+/// Selects the item whose name matches the input as described in [`fuzz_match`], asking for or
+/// failing with the candidates if there is no clear match.
+pub fn fuzz_select<'a, T: FuzzSelect>(
+    items: &'a [T],
+    input: &'_ str,
+    ambiguity: Ambiguity,
+) -> Result<&'a T> {
+    let candidates = match fuzz_match(items, input)? {
+        Matches::Clear(item) => return Ok(item),
+        Matches::Unclear(candidates) => candidates,
+    };
+    match ambiguity {
+        Ambiguity::Ask => Ok(candidates[select(
+            &format!("No clear match for `{input}`, select one"),
+            &candidates.iter().map(|c| c.name()).collect::<Vec<_>>(),
+        )?
+        .ok_or_else(|| eyre!("no selection made"))?]),
+        Ambiguity::Fail => Err(unclear(input, &candidates)),
+    }
+}
+
+/// Returns the error for an input without a clear match, listing the candidates.
+pub fn unclear<T: FuzzSelect>(input: &str, candidates: &[&T]) -> color_eyre::Report {
+    eyre!(
+        "no clear match for `{input}`, candidates are: {}",
+        candidates
+            .iter()
+            .map(|c| format!("`{}`", c.name()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+/// Returns the item that matches the input best, even if other items match just as well.
+pub fn best_match<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Option<&'a T> {
+    fuzzy_matches(items, input).first().map(|(_, item)| *item)
+}
+
+/// Returns the items that match the input with their score, best matches first.
+fn fuzzy_matches<'a, T: FuzzSelect>(items: &'a [T], input: &'_ str) -> Vec<(i64, &'a T)> {
     let matcher = SkimMatcherV2::default();
-    items
+    let mut matches = items
         .iter()
         .filter_map(|i| matcher.fuzzy_match(i.name(), input).map(|s| (s, i)))
-        .max_by(|left, right| left.0.cmp(&right.0))
-        .map(|v| v.1)
-        .ok_or_else(|| eyre!("no suitable item found, aborting"))
+        .collect::<Vec<_>>();
+    // Items are often collected from maps, so ties are ordered by name to stay stable.
+    matches.sort_by(|left, right| {
+        right
+            .0
+            .cmp(&left.0)
+            .then_with(|| left.1.name().cmp(right.1.name()))
+    });
+    matches
 }
 
 pub trait FuzzSelect {
@@ -197,6 +324,28 @@ impl FuzzSelect for Label {
     }
 }
 
+impl FuzzSelect for Filter {
+    type ID = FilterID;
+
+    fn id(&self) -> FilterID {
+        self.id.clone()
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<T: FuzzSelect> FuzzSelect for &T {
+    type ID = T::ID;
+
+    fn id(&self) -> T::ID {
+        (*self).id()
+    }
+    fn name(&self) -> &str {
+        (*self).name()
+    }
+}
+
 impl FuzzSelect for Task {
     type ID = TaskID;
 
@@ -224,6 +373,15 @@ pub fn input_content(content: &str) -> Result<String> {
         .wrap_err("No input made")
 }
 
+/// Asks a yes or no question, where no is the default.
+pub fn confirm(prompt: &str) -> Result<bool> {
+    dialoguer::Confirm::new()
+        .with_prompt(prompt)
+        .default(false)
+        .interact()
+        .wrap_err("No input made")
+}
+
 pub fn input_optional(prompt: &str, default: Option<String>) -> Result<Option<String>> {
     match dialoguer::Input::<'_, String>::new()
         .with_prompt(prompt)
@@ -300,8 +458,47 @@ mod test {
     #[test]
     fn select_best() {
         let select: Vec<Selectable> = vec![(0, "zero"), (1, "one"), (2, "two"), (3, "three")];
-        assert_eq!(fuzz_select(&select, "one").unwrap().0, 1);
-        assert_eq!(fuzz_select(&select, "w").unwrap().0, 2);
-        assert!(fuzz_select(&select, "what").is_err());
+        assert_eq!(fuzz_select(&select, "one", Ambiguity::Fail).unwrap().0, 1);
+        assert_eq!(fuzz_select(&select, "w", Ambiguity::Fail).unwrap().0, 2);
+        assert!(fuzz_select(&select, "what", Ambiguity::Fail).is_err());
+    }
+
+    #[test]
+    fn select_ambiguous() {
+        let select: Vec<Selectable> = vec![
+            (0, "Homework"),
+            (1, "Workshop"),
+            (2, "Work"),
+            (3, "Project One"),
+            (4, "Project Two"),
+        ];
+        assert_eq!(fuzz_select(&select, "work", Ambiguity::Fail).unwrap().0, 2);
+        assert_eq!(fuzz_select(&select, "WORK", Ambiguity::Fail).unwrap().0, 2);
+        assert_eq!(fuzz_select(&select, "pone", Ambiguity::Fail).unwrap().0, 3);
+        assert_eq!(
+            fuzz_select(&select, "proj", Ambiguity::Fail)
+                .unwrap_err()
+                .to_string(),
+            "no clear match for `proj`, candidates are: `Project One`, `Project Two`"
+        );
+        assert_eq!(
+            fuzz_select(&select[..2], "work", Ambiguity::Fail)
+                .unwrap_err()
+                .to_string(),
+            "no clear match for `work`, candidates are: `Workshop`, `Homework`"
+        );
+        assert_eq!(best_match(&select, "proj").unwrap().0, 3);
+    }
+
+    #[test]
+    fn select_unclear() {
+        let select: Vec<Selectable> = vec![(0, "Bookkeeping"), (1, "Travel")];
+        assert_eq!(
+            fuzz_select(&select, "og", Ambiguity::Fail)
+                .unwrap_err()
+                .to_string(),
+            "no clear match for `og`, candidates are: `Bookkeeping`"
+        );
+        assert_eq!(fuzz_select(&select, "book", Ambiguity::Fail).unwrap().0, 0);
     }
 }
@@ -1,4 +1,7 @@
-use crate::api::rest::{CreateLabel, Gateway};
+use crate::{
+    api::rest::{CreateLabel, Gateway},
+    config::Config,
+};
 use color_eyre::Result;
 
 #[derive(clap::Parser, Debug)]
@@ -7,13 +10,16 @@ pub struct Params {
     name: String,
 }
 
-pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let label = gw
         .create_label(&CreateLabel {
             name: params.name,
             ..Default::default()
         })
         .await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(&label);
+    }
     println!("created label: {}", &label);
     Ok(())
 }
@@ -1,4 +1,4 @@
-use crate::api::rest::Gateway;
+use crate::{api::rest::Gateway, config::Config, interactive::Ambiguity};
 use color_eyre::{Result, eyre::eyre};
 
 use super::{LabelSelect, label::Selection};
@@ -9,16 +9,23 @@ pub struct Params {
     labels: LabelSelect,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
-    let labels = params
-        .labels
-        .labels(&gw.labels().await?, Selection::MustChoose)?;
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let labels = params.labels.labels(
+        &gw.labels().await?,
+        Selection::MustChoose,
+        Ambiguity::new(cfg.output.is_text()),
+    )?;
     if labels.is_empty() {
         return Err(eyre!("no labels selected"));
     }
-    for label in labels {
+    for label in &labels {
         gw.delete_label(&label.id).await?;
-        println!("deleted label: {}", &label);
+        if cfg.output.is_text() {
+            println!("deleted label: {}", &label);
+        }
+    }
+    if !cfg.output.is_text() {
+        return cfg.output.list(&labels);
     }
     Ok(())
 }
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use crate::{
     api::rest::{FullLabel, Label},
-    interactive,
+    interactive::{self, Ambiguity},
 };
 use color_eyre::{Result, eyre::eyre};
 
@@ -33,7 +33,12 @@ impl LabelSelect {
         self.label_ids.is_none() && self.label_names.is_none()
     }
 
-    pub fn labels(&self, labels: &[Label], selection: Selection) -> Result<Vec<Label>> {
+    pub fn labels(
+        &self,
+        labels: &[Label],
+        selection: Selection,
+        ambiguity: Ambiguity,
+    ) -> Result<Vec<Label>> {
         let label_ids = self.label_ids.clone().unwrap_or_default();
         let mut all_labels = labels
             .iter()
@@ -80,7 +85,7 @@ impl LabelSelect {
                 .unwrap()
                 .iter()
                 .map(|label| {
-                    interactive::fuzz_select(&label_list, label)
+                    interactive::fuzz_select(&label_list, label, ambiguity)
                         .map(|label| all_labels.remove(&label.id).unwrap())
                 })
                 .collect::<Result<Vec<_>>>()?,
@@ -1,11 +1,17 @@
-use crate::api::rest::{FullLabel, Gateway};
+use crate::{
+    api::rest::{FullLabel, Gateway},
+    config::Config,
+};
 use color_eyre::Result;
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {}
 
-pub async fn list(_params: Params, gw: &Gateway) -> Result<()> {
+pub async fn list(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let labels = gw.labels().await?;
+    if !cfg.output.is_text() {
+        return cfg.output.list(&labels);
+    }
     for label in labels {
         println!("{}", &FullLabel(&label));
     }
@@ -12,12 +12,19 @@
 pub mod api;
 mod command;
 mod comments;
+mod completions;
 pub mod config;
+mod export;
+mod filters;
 mod interactive;
 mod labels;
+mod oauth;
+mod output;
 mod projects;
+mod query;
 mod sections;
 mod tasks;
+mod tui;
 
 #[doc(hidden)]
 pub use command::Arguments;
@@ -0,0 +1,305 @@
+//! Implements the Todoist OAuth authorization-code flow as an alternative to pasting an API token.
+//!
+//! A loopback listener on localhost receives the redirect from the authorization server, after
+//! which the received code is exchanged for an access token.
+use std::time::Duration;
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use uuid::Uuid;
+
+use crate::config::{Config, DEFAULT_OAUTH_PORT};
+
+lazy_static! {
+    /// The default URL of the Todoist OAuth authorization server.
+    pub static ref TODOIST_OAUTH_URL: url::Url = {
+        url::Url::parse("https://todoist.com/").unwrap()
+    };
+}
+
+/// Permissions requested when authorizing doist.
+const OAUTH_SCOPE: &str = "data:read_write,data:delete,project:delete";
+
+/// How long to wait for the user to finish authorizing in the browser.
+const OAUTH_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(clap::Args, Debug)]
+pub struct Params {
+    /// Client ID of the Todoist app to authorize. Overrides `oauth_client_id` in the config.
+    #[arg(long = "client_id", requires = "oauth")]
+    client_id: Option<String>,
+    /// Client secret of the Todoist app to authorize. Overrides `oauth_client_secret` in the
+    /// config.
+    #[arg(long = "client_secret", requires = "oauth")]
+    client_secret: Option<String>,
+    /// Port on localhost that receives the OAuth redirect. The redirect URL of the app must be
+    /// set to `http://localhost:<port>/`.
+    #[arg(long = "port", requires = "oauth")]
+    port: Option<u16>,
+}
+
+/// Response of the token exchange endpoint.
+#[derive(Deserialize)]
+struct AccessToken {
+    access_token: String,
+}
+
+/// Talks to the Todoist OAuth authorization server.
+pub struct OAuth {
+    client: reqwest::Client,
+    client_id: String,
+    client_secret: String,
+    url: url::Url,
+}
+
+impl OAuth {
+    /// Creates a new [`OAuth`] client for the given app credentials and authorization server.
+    pub fn new(client_id: &str, client_secret: &str, url: &url::Url) -> OAuth {
+        OAuth {
+            client: reqwest::Client::new(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            url: url.clone(),
+        }
+    }
+
+    /// Returns the URL the user has to open to authorize doist.
+    pub fn authorize_url(&self, state: &str) -> Result<url::Url> {
+        let mut url = self.url.join("oauth/authorize")?;
+        url.query_pairs_mut()
+            .append_pair("client_id", &self.client_id)
+            .append_pair("scope", OAUTH_SCOPE)
+            .append_pair("state", state);
+        Ok(url)
+    }
+
+    /// Exchanges the authorization code for an access token.
+    pub async fn exchange(&self, code: &str) -> Result<String> {
+        let resp = self
+            .client
+            .post(self.url.join("oauth/access_token")?)
+            .form(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("code", code),
+            ])
+            .timeout(Duration::from_secs(30))
+            .send()
+            .await
+            .wrap_err("unable to send token request")?;
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .wrap_err("unable to read token response")?;
+        if !status.is_success() {
+            return Err(eyre!(
+                "Bad response from OAuth server: {} - {}",
+                status,
+                text
+            ));
+        }
+        let token: AccessToken =
+            serde_json::from_str(&text).wrap_err("unable to parse token response")?;
+        Ok(token.access_token)
+    }
+}
+
+/// Waits for the authorization server to redirect the browser to the listener and returns the
+/// received authorization code.
+///
+/// Requests that don't carry OAuth parameters (like a browser asking for a favicon) are ignored.
+pub async fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    loop {
+        let (stream, _) = listener
+            .accept()
+            .await
+            .wrap_err("unable to accept OAuth redirect")?;
+        if let Some(result) = handle_redirect(stream, state).await? {
+            return result;
+        }
+    }
+}
+
+/// Reads a single redirect request and answers the browser. Returns [`None`] if the request was
+/// not an OAuth redirect.
+async fn handle_redirect(stream: TcpStream, state: &str) -> Result<Option<Result<String>>> {
+    let mut stream = BufReader::new(stream);
+    let mut request_line = String::new();
+    stream
+        .read_line(&mut request_line)
+        .await
+        .wrap_err("unable to read OAuth redirect")?;
+    // Request line looks like `GET /?code=...&state=... HTTP/1.1`
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let url = url::Url::parse("http://localhost/")?
+        .join(path)
+        .wrap_err("bad OAuth redirect")?;
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+    };
+    let result = match (query("code"), query("state"), query("error")) {
+        (_, _, Some(error)) => Err(eyre!("authorization was denied: {}", error)),
+        (Some(code), Some(s), None) if s == state => Ok(code),
+        (Some(_), _, None) => Err(eyre!("OAuth state mismatch, aborting")),
+        (None, _, None) => {
+            respond(stream.get_mut(), "404 Not Found", "Not found").await?;
+            return Ok(None);
+        }
+    };
+    let body = match &result {
+        Ok(_) => "doist was authorized, you can close this window now.",
+        Err(_) => "doist was not authorized, check the terminal for details.",
+    };
+    respond(stream.get_mut(), "200 OK", body).await?;
+    Ok(Some(result))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> Result<()> {
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .as_bytes(),
+        )
+        .await
+        .wrap_err("unable to respond to OAuth redirect")
+}
+
+/// Runs the full OAuth flow and stores the resulting token in the config.
+pub async fn login(params: Params, cfg: &mut Config) -> Result<()> {
+    let client_id = params
+        .client_id
+        .or_else(|| cfg.oauth_client_id.clone())
+        .ok_or_else(|| {
+            eyre!("No OAuth client ID specified. Use --client_id or set oauth_client_id in the config.")
+        })?;
+    let client_secret = params
+        .client_secret
+        .or_else(|| cfg.oauth_client_secret.clone())
+        .ok_or_else(|| {
+            eyre!("No OAuth client secret specified. Use --client_secret or set oauth_client_secret in the config.")
+        })?;
+    let port = params.port.or(cfg.oauth_port).unwrap_or(DEFAULT_OAUTH_PORT);
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .wrap_err_with(|| format!("unable to listen on port {port}"))?;
+
+    let oauth = OAuth::new(&client_id, &client_secret, &cfg.oauth_url());
+    let state = Uuid::new_v4().to_string();
+    println!(
+        "Open the following URL in your browser to authorize doist:\n{}",
+        oauth.authorize_url(&state)?
+    );
+    let code = tokio::time::timeout(OAUTH_TIMEOUT, wait_for_code(&listener, &state))
+        .await
+        .wrap_err("timed out waiting for authorization")??;
+    cfg.token = Some(oauth.exchange(&code).await?);
+    cfg.save()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use wiremock::{
+        Mock, MockServer, ResponseTemplate,
+        matchers::{body_string_contains, method, path},
+    };
+
+    use super::*;
+
+    fn oauth(ms: &MockServer) -> OAuth {
+        OAuth::new("client", "secret", &ms.uri().parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn authorize_url() {
+        let mock_server = MockServer::start().await;
+        let url = oauth(&mock_server).authorize_url("abc").unwrap();
+        assert_eq!(url.path(), "/oauth/authorize");
+        let query = url.query_pairs().collect::<Vec<_>>();
+        assert!(query.contains(&("client_id".into(), "client".into())));
+        assert!(query.contains(&("state".into(), "abc".into())));
+        assert!(query.contains(&("scope".into(), OAUTH_SCOPE.into())));
+    }
+
+    #[tokio::test]
+    async fn exchanges_code() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth/access_token"))
+            .and(body_string_contains("code=thecode"))
+            .and(body_string_contains("client_secret=secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "access_token": "TOKEN",
+                "token_type": "Bearer",
+            })))
+            .mount(&mock_server)
+            .await;
+        let token = oauth(&mock_server).exchange("thecode").await.unwrap();
+        mock_server.verify().await;
+        assert_eq!(token, "TOKEN");
+    }
+
+    #[tokio::test]
+    async fn exchange_fails() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/oauth/access_token"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "bad_authorization_code",
+            })))
+            .mount(&mock_server)
+            .await;
+        assert!(oauth(&mock_server).exchange("thecode").await.is_err());
+    }
+
+    async fn redirect(query: &str) -> Result<String> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let query = query.to_string();
+        let browser = tokio::spawn(async move {
+            let client = reqwest::Client::new();
+            client
+                .get(format!("http://{addr}/favicon.ico"))
+                .send()
+                .await
+                .unwrap();
+            client
+                .get(format!("http://{addr}/?{query}"))
+                .send()
+                .await
+                .unwrap()
+        });
+        let code = wait_for_code(&listener, "abc").await;
+        assert!(browser.await?.status().is_success());
+        code
+    }
+
+    #[tokio::test]
+    async fn receives_code() {
+        assert_eq!(redirect("code=thecode&state=abc").await.unwrap(), "thecode");
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_state() {
+        assert!(redirect("code=thecode&state=bad").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_denied() {
+        assert!(redirect("error=access_denied&state=abc").await.is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Describes how command results are written to stdout.
+use color_eyre::Result;
+use serde::Serialize;
+
+/// Output selects between the human-readable output and machine-readable formats.
+#[derive(clap::ValueEnum, Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// Human-readable output meant for the terminal.
+    #[default]
+    Text,
+    /// A single JSON document. Lists are written as a JSON array.
+    Json,
+    /// One JSON document per line. Lists write one line per item.
+    Jsonl,
+    /// Comma-separated values with a header row. Only supported for task listings.
+    Csv,
+    /// Tab-separated values with a header row. Only supported for task listings.
+    Tsv,
+}
+
+impl Output {
+    /// Returns true if the output is meant for humans, which also allows interactive prompts.
+    pub fn is_text(&self) -> bool {
+        *self == Output::Text
+    }
+
+    /// Returns true if the output is a table, which only task listings can produce.
+    pub fn is_tabular(&self) -> bool {
+        matches!(self, Output::Csv | Output::Tsv)
+    }
+
+    /// Writes a single serialized item.
+    pub fn item<T: Serialize>(&self, item: &T) -> Result<()> {
+        match self {
+            Output::Json => println!("{}", serde_json::to_string_pretty(item)?),
+            _ => println!("{}", serde_json::to_string(item)?),
+        }
+        Ok(())
+    }
+
+    /// Writes a list of serialized items.
+    pub fn list<T: Serialize>(&self, items: &[T]) -> Result<()> {
+        match self {
+            Output::Json => println!("{}", serde_json::to_string_pretty(items)?),
+            _ => {
+                for item in items {
+                    println!("{}", serde_json::to_string(item)?);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a header and the rows of a table.
+    pub fn table<S: AsRef<str>>(&self, header: &[S], rows: &[Vec<String>]) -> Result<()> {
+        let (separator, escape): (&str, fn(&str) -> String) = match self {
+            Output::Tsv => ("\t", tsv_field),
+            _ => (",", csv_field),
+        };
+        println!(
+            "{}",
+            header
+                .iter()
+                .map(|h| escape(h.as_ref()))
+                .collect::<Vec<_>>()
+                .join(separator)
+        );
+        for row in rows {
+            println!(
+                "{}",
+                row.iter()
+                    .map(|f| escape(f))
+                    .collect::<Vec<_>>()
+                    .join(separator)
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if it contains characters that would otherwise break the row.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// TSV has no escaping, so tabs and line breaks are replaced with spaces.
+fn tsv_field(field: &str) -> String {
+    field.replace(['\t', '\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escapes_csv() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn escapes_tsv() {
+        assert_eq!(tsv_field("a,b"), "a,b");
+        assert_eq!(tsv_field("a\tb\nc"), "a b c");
+    }
+}
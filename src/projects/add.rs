@@ -1,4 +1,7 @@
-use crate::api::rest::{CreateProject, Gateway};
+use crate::{
+    api::rest::{CreateProject, Gateway},
+    config::Config,
+};
 use color_eyre::Result;
 
 #[derive(clap::Parser, Debug)]
@@ -7,13 +10,16 @@ pub struct Params {
     name: String,
 }
 
-pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let project = gw
         .create_project(&CreateProject {
             name: params.name,
             ..Default::default()
         })
         .await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(&project);
+    }
     println!("created project: {}", &project);
     Ok(())
 }
@@ -0,0 +1,295 @@
+//! Shows the tasks of a project as a board, with a column for each section.
+use color_eyre::{Result, eyre::eyre};
+use owo_colors::{OwoColorize, Stream};
+use ratatui::crossterm::terminal;
+
+use crate::{
+    api::{
+        rest::{Gateway, MoveTask, Priority, Project, Section, Task},
+        tree::Tree,
+    },
+    interactive,
+};
+
+/// Width of the board if the terminal size is unknown.
+const DEFAULT_WIDTH: usize = 80;
+/// Bounds of the width of a single column, including its border.
+const MIN_COLUMN_WIDTH: usize = 20;
+const MAX_COLUMN_WIDTH: usize = 40;
+/// Space between two columns.
+const GAP: &str = "  ";
+
+/// A column of the board with the top level tasks of a section.
+struct Column<'a> {
+    section: Option<&'a Section>,
+    tasks: Vec<&'a Tree<Task>>,
+}
+
+impl Column<'_> {
+    fn title(&self) -> &str {
+        self.section.map_or("(No section)", |s| s.name.as_str())
+    }
+
+    /// Where tasks need to be moved to, to end up in this column.
+    fn target(&self, project: &Project) -> MoveTask {
+        match self.section {
+            Some(section) => MoveTask {
+                section_id: Some(section.id.clone()),
+                ..Default::default()
+            },
+            None => MoveTask {
+                project_id: Some(project.id.clone()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+pub struct Board<'a> {
+    columns: Vec<Column<'a>>,
+}
+
+impl<'a> Board<'a> {
+    /// Creates the board with a column for each section in order. Tasks without a section get
+    /// their own column in front.
+    pub fn new(tasks: &'a [Tree<Task>], mut sections: Vec<&'a Section>) -> Board<'a> {
+        sections.sort();
+        let mut tasks = tasks.iter().collect::<Vec<_>>();
+        tasks.sort();
+        let column = |section: Option<&'a Section>| Column {
+            section,
+            tasks: tasks
+                .iter()
+                .filter(|t| t.section_id.as_ref() == section.map(|s| &s.id))
+                .copied()
+                .collect(),
+        };
+        let mut columns = Vec::new();
+        let unsectioned = column(None);
+        if !unsectioned.tasks.is_empty() || sections.is_empty() {
+            columns.push(unsectioned);
+        }
+        columns.extend(sections.into_iter().map(|s| column(Some(s))));
+        Board { columns }
+    }
+
+    /// Renders the columns side by side, fitting them into the given width where possible.
+    pub fn render(&self, width: usize) -> String {
+        let gaps = GAP.len() * self.columns.len().saturating_sub(1);
+        let column_width = (width.saturating_sub(gaps) / self.columns.len().max(1))
+            .clamp(MIN_COLUMN_WIDTH, MAX_COLUMN_WIDTH);
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| column_lines(c, column_width))
+            .collect::<Vec<_>>();
+        let height = columns.iter().map(Vec::len).max().unwrap_or_default();
+        let mut output = String::new();
+        for row in 0..height {
+            let line = columns
+                .iter()
+                .map(|lines| match lines.get(row) {
+                    Some(line) => line.clone(),
+                    None => " ".repeat(column_width),
+                })
+                .collect::<Vec<_>>()
+                .join(GAP);
+            output.push_str(line.trim_end());
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Returns the lines of a column, each padded to the width of the column.
+fn column_lines(column: &Column<'_>, width: usize) -> Vec<String> {
+    let inner = width - 4;
+    let title = truncate(
+        &format!("{} ({})", column.title(), column.tasks.len()),
+        width,
+    );
+    let mut lines = vec![
+        pad(&title, width)
+            .if_supports_color(Stream::Stdout, |text| text.bold())
+            .to_string(),
+        "═".repeat(width),
+    ];
+    for task in &column.tasks {
+        lines.push(format!("┌{}┐", "─".repeat(width - 2)));
+        for line in wrap(&task.content, inner) {
+            lines.push(format!("│ {} │", pad(&line, inner)));
+        }
+        let details = details(task);
+        if !details.is_empty() {
+            lines.push(format!("│ {} │", pad(&truncate(&details, inner), inner)));
+        }
+        lines.push(format!("└{}┘", "─".repeat(width - 2)));
+    }
+    lines
+}
+
+/// Describes the priority, due date, labels and subtasks of a task in a single line.
+fn details(task: &Tree<Task>) -> String {
+    let mut details = Vec::new();
+    // The priority is written out here, as its colors would break the padding of the card.
+    if task.priority != Priority::Normal {
+        details.push(task.priority.label().to_string());
+    }
+    if let Some(due) = &task.due {
+        details.push(due.string.clone());
+    }
+    details.extend(task.labels.iter().map(|l| format!("@{l}")));
+    if !task.subitems.is_empty() {
+        details.push(format!("+{} subtasks", task.subitems.len()));
+    }
+    details.join(" ")
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{text}{}",
+        " ".repeat(width.saturating_sub(text.chars().count()))
+    )
+}
+
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut text = text.chars().take(width - 1).collect::<String>();
+    text.push('…');
+    text
+}
+
+/// Wraps the text at word boundaries, truncating words that are too long for a line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let word = truncate(word, width);
+        match lines.last_mut() {
+            Some(line) if line.chars().count() + 1 + word.chars().count() <= width => {
+                line.push(' ');
+                line.push_str(&word);
+            }
+            _ => lines.push(word),
+        }
+    }
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// Returns the width of the terminal the board is shown in.
+pub fn width() -> usize {
+    terminal::size()
+        .map(|(width, _)| width as usize)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Lets the user pick a card and move it to the section next to it until nothing is selected.
+pub async fn interactive(project: &Project, gw: &Gateway) -> Result<()> {
+    loop {
+        let (tasks, sections) = fetch(project, gw).await?;
+        let board = Board::new(&tasks, sections.iter().collect());
+        print!("{}", board.render(width()));
+        let cards = board
+            .columns
+            .iter()
+            .enumerate()
+            .flat_map(|(index, column)| column.tasks.iter().map(move |task| (index, *task)))
+            .collect::<Vec<_>>();
+        if cards.is_empty() {
+            return Err(eyre!("no tasks in {} to move", project.name));
+        }
+        let Some(card) = interactive::select(
+            "Select card to move",
+            &cards
+                .iter()
+                .map(|(index, task)| format!("{}: {}", board.columns[*index].title(), task.content))
+                .collect::<Vec<_>>(),
+        )?
+        else {
+            return Ok(());
+        };
+        let (index, task) = cards[card];
+        let targets = [
+            index.checked_sub(1).map(|i| ("←", i)),
+            Some(("→", index + 1)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|(arrow, i)| board.columns.get(i).map(|c| (arrow, c)))
+        .collect::<Vec<_>>();
+        let Some(target) = interactive::select(
+            "Move card to",
+            &targets
+                .iter()
+                .map(|(arrow, column)| format!("{arrow} {}", column.title()))
+                .collect::<Vec<_>>(),
+        )?
+        else {
+            continue;
+        };
+        let target = targets[target].1;
+        gw.move_task(&task.id, &target.target(project)).await?;
+        println!("moved {} to {}", task.content, target.title());
+    }
+}
+
+/// Fetches the task trees and sections of the project.
+pub async fn fetch(project: &Project, gw: &Gateway) -> Result<(Vec<Tree<Task>>, Vec<Section>)> {
+    let (tasks, sections) = tokio::try_join!(gw.tasks(None), gw.sections())?;
+    let tasks = tasks
+        .into_iter()
+        .filter(|t| t.project_id == project.id)
+        .collect();
+    let sections = sections
+        .into_iter()
+        .filter(|s| s.project_id == project.id)
+        .collect();
+    Ok((Tree::from_items(tasks)?, sections))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn renders_columns() {
+        let tasks = Tree::from_items(vec![
+            Task {
+                section_id: Some("1".to_string()),
+                priority: Priority::Urgent,
+                labels: vec!["sprint".to_string()],
+                ..Task::new("1", "Write the release notes for the next version")
+            },
+            Task {
+                parent_id: Some("1".to_string()),
+                section_id: Some("1".to_string()),
+                ..Task::new("2", "Collect changes")
+            },
+            Task::new("3", "Triage"),
+        ])
+        .unwrap();
+        let sections = [
+            Section::new("2", "", "Done"),
+            Section::new("1", "", "Doing"),
+        ];
+        let board = Board::new(&tasks, sections.iter().collect());
+        assert_eq!(
+            board.render(50),
+            "\
+(No section) (1)      Doing (1)             Done (0)
+════════════════════  ════════════════════  ════════════════════
+┌──────────────────┐  ┌──────────────────┐
+│ Triage           │  │ Write the        │
+└──────────────────┘  │ release notes    │
+                      │ for the next     │
+                      │ version          │
+                      │ p1 @sprint +1 s… │
+                      └──────────────────┘
+"
+        );
+    }
+}
@@ -2,6 +2,7 @@ use color_eyre::Result;
 
 use crate::{
     api::rest::{CreateComment, FullComment, Gateway, Project, ThreadID},
+    config::Config,
     interactive,
 };
 
@@ -14,9 +15,11 @@ pub struct Params {
 }
 
 /// Creates a new comment for a project.
-pub async fn comment(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn comment(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
     let comment = gw
         .create_comment(&CreateComment {
             thread: ThreadID::Project {
@@ -25,6 +28,9 @@ pub async fn comment(params: Params, gw: &Gateway) -> Result<()> {
             content: params.content,
         })
         .await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(&comment);
+    }
     println!("created comment: {}", FullComment(&comment));
     Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{Gateway, Project},
+    config::Config,
     interactive,
 };
 use color_eyre::Result;
@@ -10,10 +11,15 @@ pub struct Params {
     project: interactive::Selection<Project>,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
     gw.delete_project(&project.id).await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(project);
+    }
     println!("deleted project: {}", &project);
     Ok(())
 }
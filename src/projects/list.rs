@@ -1,7 +1,11 @@
 use std::collections::HashMap;
 
-use crate::api::rest::{Gateway, Project, Task};
+use crate::{
+    api::rest::{Gateway, Project, Task},
+    config::Config,
+};
 use color_eyre::{Result, eyre::eyre};
+use serde::Serialize;
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
@@ -10,8 +14,16 @@ pub struct Params {
     pub filter: Option<String>,
 }
 
+/// Used to serialize a [`Project`] together with the amount of tasks passing the filter.
+#[derive(Serialize)]
+struct FilteredProject<'a> {
+    #[serde(flatten)]
+    project: &'a Project,
+    task_count: usize,
+}
+
 /// Lists available projects.
-pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
     if let Some(filter) = params.filter {
         let tasks = gw.tasks(Some(&filter)).await?;
@@ -19,11 +31,25 @@ pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
             return Err(eyre!("no tasks match the given filter"))?;
         }
         let projects = filtered_projects(&projects, &tasks)?;
+        if !cfg.output.is_text() {
+            return cfg.output.list(
+                &projects
+                    .into_iter()
+                    .map(|(project, task_count)| FilteredProject {
+                        project,
+                        task_count,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
         for (project, tasks) in projects.iter() {
             println!("{} (Tasks: {})", &project, tasks);
         }
         return Ok(());
     }
+    if !cfg.output.is_text() {
+        return cfg.output.list(&projects);
+    }
     for project in projects.iter() {
         println!("{}", &project);
     }
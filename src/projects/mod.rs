@@ -1,5 +1,6 @@
 //! Controls things that work with [`crate::api::rest::Project`]s.
 pub mod add;
+mod board;
 pub mod comment;
 pub mod delete;
 pub mod list;
@@ -1,24 +1,62 @@
 use crate::{
-    api::rest::{Gateway, Project},
-    comments, interactive,
-    projects::state::State,
+    api::rest::{Comment, Gateway, Project, Section, ViewStyle},
+    comments,
+    config::Config,
+    interactive,
+    projects::{
+        board::{self, Board},
+        state::State,
+    },
 };
 use color_eyre::{Result, eyre::eyre};
+use serde::Serialize;
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     #[clap(flatten)]
     project: interactive::Selection<Project>,
+    /// Shows the tasks as a board with a column for each section. This is the default for
+    /// projects that use the board view in Todoist.
+    #[arg(short = 'b', long = "board", conflicts_with = "list")]
+    board: bool,
+    /// Shows the sections as a list, even if the project uses the board view.
+    #[arg(short = 'l', long = "list")]
+    list: bool,
+    /// Shows the board and lets you move cards to the section next to them.
+    #[arg(short = 'i', long = "interactive", conflicts_with = "list")]
+    interactive: bool,
 }
 
-pub async fn view(params: Params, gw: &Gateway) -> Result<()> {
+/// Used to serialize a [`Project`] with everything that is shown in the project view.
+#[derive(Serialize)]
+struct ProjectView<'a> {
+    #[serde(flatten)]
+    project: &'a Project,
+    subprojects: Vec<&'a Project>,
+    sections: Vec<&'a Section>,
+    comments: Vec<Comment>,
+}
+
+pub async fn view(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
     // TODO: no refetch here
     let state = State::fetch_tree(gw).await?;
     let tree = state
         .project(&project.id)
         .ok_or_else(|| eyre!("full project list contained invalid data"))?;
+    if !cfg.output.is_text() {
+        let mut sections = state.sections(&project.id);
+        sections.sort();
+        return cfg.output.item(&ProjectView {
+            project: &tree.item,
+            subprojects: tree.subitems.iter().map(|p| &p.item).collect(),
+            sections,
+            comments: gw.project_comments(&project.id).await?,
+        });
+    }
     println!("Project: {}", &tree.item);
     if !tree.subitems.is_empty() {
         println!("Subprojects:");
@@ -26,11 +64,22 @@ pub async fn view(params: Params, gw: &Gateway) -> Result<()> {
             println!("{}", project.item)
         }
     }
-    let sections = state.sections(&project.id);
-    if !sections.is_empty() {
-        println!("Sections:");
-        for section in sections {
-            println!("{section}")
+    let is_board = params.board || (tree.view_style == ViewStyle::Board && !params.list);
+    if params.interactive {
+        board::interactive(&tree.item, gw).await?;
+    } else if is_board {
+        let (tasks, sections) = board::fetch(&tree.item, gw).await?;
+        print!(
+            "{}",
+            Board::new(&tasks, sections.iter().collect()).render(board::width())
+        );
+    } else {
+        let sections = state.sections(&project.id);
+        if !sections.is_empty() {
+            println!("Sections:");
+            for section in sections {
+                println!("{section}")
+            }
         }
     }
     let comments = gw.project_comments(&project.id).await?;
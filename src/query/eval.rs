@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate, Utc};
+
+use crate::api::rest::{Priority, Project, ProjectID, Section, SectionID, Task};
+
+use super::{Day, Query, Term};
+
+/// The lookups and the current time that a query needs to match tasks.
+pub struct Context<'a> {
+    pub projects: &'a HashMap<ProjectID, Project>,
+    pub sections: &'a HashMap<SectionID, Section>,
+    pub now: DateTime<Utc>,
+}
+
+impl Context<'_> {
+    fn today(&self) -> NaiveDate {
+        self.now.date_naive()
+    }
+
+    /// Returns whether the project or one of its parents matches the name.
+    fn in_project(&self, id: &ProjectID, name: &str, subprojects: bool) -> bool {
+        let mut project = self.projects.get(id);
+        // Bounded by the number of projects in case the parents form a cycle.
+        for _ in 0..=self.projects.len() {
+            let Some(current) = project else {
+                return false;
+            };
+            if matches_name(name, &current.name) {
+                return true;
+            }
+            if !subprojects {
+                return false;
+            }
+            project = current
+                .parent_id
+                .as_ref()
+                .and_then(|p| self.projects.get(p));
+        }
+        false
+    }
+}
+
+impl Day {
+    /// Returns the date of the day relative to today.
+    fn resolve(&self, today: NaiveDate) -> Option<NaiveDate> {
+        match *self {
+            Day::Relative(days) if days < 0 => {
+                today.checked_sub_days(Days::new(days.unsigned_abs()))
+            }
+            Day::Relative(days) => today.checked_add_days(Days::new(days as u64)),
+            Day::ThisYear { month, day } => NaiveDate::from_ymd_opt(today.year(), month, day),
+            Day::Date(date) => Some(date),
+        }
+    }
+}
+
+impl Query {
+    /// Returns whether the task matches the query.
+    pub fn matches(&self, task: &Task, ctx: &Context) -> bool {
+        match self {
+            Query::And(a, b) => a.matches(task, ctx) && b.matches(task, ctx),
+            Query::Or(a, b) => a.matches(task, ctx) || b.matches(task, ctx),
+            Query::Not(query) => !query.matches(task, ctx),
+            Query::Term(term) => term.matches(task, ctx),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, task: &Task, ctx: &Context) -> bool {
+        let today = ctx.today();
+        let due = task.due.as_ref().map(|d| d.date);
+        let compare =
+            |day: &Day, f: fn(NaiveDate, NaiveDate) -> bool| match (due, day.resolve(today)) {
+                (Some(due), Some(day)) => f(due, day),
+                _ => false,
+            };
+        match self {
+            Term::All => true,
+            Term::Due(day) => compare(day, |due, day| due == day),
+            Term::DueBefore(day) => compare(day, |due, day| due < day),
+            Term::DueAfter(day) => compare(day, |due, day| due > day),
+            Term::Days(days) => match (due, today.checked_add_days(Days::new(*days))) {
+                (Some(due), Some(end)) => due >= today && due < end,
+                _ => false,
+            },
+            Term::Overdue => match &task.due {
+                Some(due) => match &due.exact {
+                    Some(exact) => exact.datetime < ctx.now,
+                    None => due.date < today,
+                },
+                None => false,
+            },
+            Term::NoDate => due.is_none(),
+            Term::Recurring => task.due.as_ref().is_some_and(|d| d.is_recurring),
+            // The priority display is reversed as in the actual desktop client compared to the API.
+            Term::Priority(priority) => {
+                let priority = match priority {
+                    1 => Priority::Urgent,
+                    2 => Priority::VeryHigh,
+                    3 => Priority::High,
+                    _ => Priority::Normal,
+                };
+                task.priority == priority
+            }
+            Term::Project { name, subprojects } => {
+                ctx.in_project(&task.project_id, name, *subprojects)
+            }
+            Term::Section(name) => task
+                .section_id
+                .as_ref()
+                .and_then(|s| ctx.sections.get(s))
+                .is_some_and(|s| matches_name(name, &s.name)),
+            Term::Label(name) => task.labels.iter().any(|l| matches_name(name, l)),
+            Term::NoLabels => task.labels.is_empty(),
+            Term::Subtask => task.parent_id.is_some(),
+            Term::Search(text) => task.content.to_lowercase().contains(&text.to_lowercase()),
+            Term::Unknown(_) => false,
+        }
+    }
+}
+
+/// Compares names ignoring case, where `*` in the pattern matches any text.
+pub fn matches_name(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.to_lowercase(), name.to_lowercase());
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = name.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::rest::{DueDate, ExactTime};
+
+    use super::*;
+
+    fn due(id: &str, date: &str, time: Option<&str>) -> Task {
+        Task {
+            due: Some(DueDate {
+                string: date.to_string(),
+                date: date.parse().unwrap(),
+                is_recurring: false,
+                exact: time.map(|time| ExactTime {
+                    datetime: DateTime::parse_from_rfc3339(&format!("{date}T{time}Z")).unwrap(),
+                    timezone: "UTC".to_string(),
+                }),
+            }),
+            ..Task::new(id, id)
+        }
+    }
+
+    fn project(id: &str, name: &str, parent: Option<&str>) -> (ProjectID, Project) {
+        (
+            id.to_string(),
+            Project {
+                parent_id: parent.map(str::to_string),
+                ..Project::new(id, name)
+            },
+        )
+    }
+
+    #[test]
+    fn matches_tasks() {
+        let projects = HashMap::from([
+            project("1", "Work", None),
+            project("2", "Meetings", Some("1")),
+            project("3", "Home", None),
+        ]);
+        let sections = HashMap::from([("1".to_string(), Section::new("1", "2", "Weekly"))]);
+        let ctx = Context {
+            projects: &projects,
+            sections: &sections,
+            now: DateTime::parse_from_rfc3339("2022-08-26T12:00:00Z")
+                .unwrap()
+                .into(),
+        };
+        let tasks = [
+            Task {
+                project_id: "2".to_string(),
+                section_id: Some("1".to_string()),
+                labels: vec!["Office".to_string()],
+                ..due("standup", "2022-08-26", Some("09:00:00"))
+            },
+            Task {
+                project_id: "1".to_string(),
+                priority: Priority::Urgent,
+                ..due("report", "2022-08-26", Some("18:00:00"))
+            },
+            Task {
+                project_id: "3".to_string(),
+                ..due("laundry", "2022-08-24", None)
+            },
+            Task {
+                project_id: "3".to_string(),
+                ..Task::new("groceries", "Buy groceries")
+            },
+        ];
+        let ids = |query: &str| {
+            let query = Query::parse(query).unwrap();
+            tasks
+                .iter()
+                .filter(|t| query.matches(t, &ctx))
+                .map(|t| t.id.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids("overdue"), vec!["standup", "laundry"]);
+        assert_eq!(ids("today & !overdue"), vec!["report"]);
+        assert_eq!(ids("#Work"), vec!["report"]);
+        assert_eq!(ids("##work"), vec!["standup", "report"]);
+        assert_eq!(ids("#*s"), vec!["standup"]);
+        assert_eq!(ids("/Weekly | p1"), vec!["standup", "report"]);
+        assert_eq!(ids("@office"), vec!["standup"]);
+        assert_eq!(ids("no date | search: LAUND"), vec!["laundry", "groceries"]);
+        assert_eq!(ids("due before: tomorrow & #Home"), vec!["laundry"]);
+        assert_eq!(ids("due after: Aug 24"), vec!["standup", "report"]);
+        assert_eq!(ids("!(#Home | p1) & all"), vec!["standup"]);
+    }
+
+    #[test]
+    fn names() {
+        assert!(matches_name("work", "Work"));
+        assert!(!matches_name("work", "Homework"));
+        assert!(matches_name("*work", "Homework"));
+        assert!(matches_name("h*e*k", "Homework"));
+        assert!(!matches_name("ho*meh", "Home"));
+    }
+}
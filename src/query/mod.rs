@@ -0,0 +1,113 @@
+//! Parses and evaluates Todoist filter queries locally, so that tasks can be filtered without
+//! sending the query to the Todoist API.
+//!
+//! The supported syntax is a subset of the [Todoist filter
+//! language](https://todoist.com/help/articles/introduction-to-filters-V98wIH): terms like
+//! `today`, `p1`, `#Project` or `due before: tomorrow` can be combined with `&`, `|`, `!` and
+//! parentheses.
+mod eval;
+mod parse;
+
+use std::ops::Range;
+
+use chrono::NaiveDate;
+
+pub use eval::{Context, matches_name};
+pub use parse::Error;
+
+/// A parsed filter query that can be matched against tasks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Term(Term),
+}
+
+/// A single condition of a query that a task either matches or not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    /// Matches every task.
+    All,
+    /// Tasks due on the given day.
+    Due(Day),
+    /// Tasks due strictly before the given day.
+    DueBefore(Day),
+    /// Tasks due strictly after the given day.
+    DueAfter(Day),
+    /// Tasks due from today up to the given number of days, like `7 days`.
+    Days(u64),
+    /// Tasks due before today, or before now for tasks with an exact time.
+    Overdue,
+    /// Tasks without a due date.
+    NoDate,
+    /// Tasks with a recurring due date.
+    Recurring,
+    /// Tasks with the priority as shown in the client, where 1 is urgent and 4 is normal.
+    Priority(u8),
+    /// Tasks in a project with a matching name, and in its subprojects if `subprojects` is set.
+    Project { name: String, subprojects: bool },
+    /// Tasks in a section with a matching name.
+    Section(String),
+    /// Tasks with a matching label.
+    Label(String),
+    /// Tasks without any labels.
+    NoLabels,
+    /// Tasks that have a parent task.
+    Subtask,
+    /// Tasks whose content contains the text, ignoring case.
+    Search(String),
+    /// A term that can't be evaluated locally, but might be understood by the Todoist API.
+    Unknown(String),
+}
+
+/// A day in a query, which might be relative to the current day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day {
+    /// The number of days from today, so `0` is today and `-1` yesterday.
+    Relative(i64),
+    /// A day in the current year, like `Sep 1`.
+    ThisYear {
+        month: u32,
+        day: u32,
+    },
+    Date(NaiveDate),
+}
+
+impl Query {
+    /// Parses a filter query like `(today | overdue) & #Work`.
+    pub fn parse(query: &str) -> Result<Query, Error> {
+        parse::parse(query)
+    }
+
+    /// Checks the syntax of a filter without requiring every term to be evaluable locally, as
+    /// the Todoist API understands more terms. The filter can consist of multiple comma separated
+    /// queries. Returns the terms of the filter with their position in the filter.
+    pub fn check(query: &str) -> Result<Vec<(Term, Range<usize>)>, Error> {
+        parse::check(query)
+    }
+}
+
+/// Splits a filter like `today, overdue` into its comma separated queries, which are shown as
+/// separate lists by Todoist.
+pub fn split(filter: &str) -> Vec<&str> {
+    parse::split(filter)
+}
+
+/// Shows the query with the span underlined by carets and the message next to it.
+pub fn annotate(query: &str, span: &Range<usize>, message: &str) -> String {
+    let offset = query[..span.start].chars().count();
+    let width = query[span.clone()].chars().count().max(1);
+    format!(
+        "{query}\n{}{} {message}",
+        " ".repeat(offset),
+        "^".repeat(width)
+    )
+}
+
+impl Error {
+    /// Shows where the error occurred in the query.
+    pub fn diagnostic(&self, query: &str) -> String {
+        annotate(query, &self.span, &self.message)
+    }
+}
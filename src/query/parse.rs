@@ -0,0 +1,499 @@
+use std::ops::Range;
+
+use chrono::{Datelike, NaiveDate};
+use thiserror::Error;
+
+use super::{Day, Query, Term};
+
+/// Describes why a query could not be parsed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{message}")]
+pub struct Error {
+    pub message: String,
+    /// The byte range in the query that caused the error.
+    pub span: Range<usize>,
+}
+
+impl Error {
+    fn new(message: impl Into<String>, span: Range<usize>) -> Error {
+        Error {
+            message: message.into(),
+            span,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Comma,
+    Term(String),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: Kind,
+    span: Range<usize>,
+}
+
+/// Splits the query into operators and terms. Terms run until the next operator, and operator
+/// characters can be used in terms by escaping them with `\`.
+fn lex(query: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let kind = match c {
+            c if c.is_whitespace() => continue,
+            '&' => Kind::And,
+            '|' => Kind::Or,
+            '!' => Kind::Not,
+            '(' => Kind::Open,
+            ')' => Kind::Close,
+            ',' => Kind::Comma,
+            _ => {
+                let mut text = String::new();
+                let mut end = start;
+                let mut next = Some((start, c));
+                while let Some((index, c)) = next {
+                    if matches!(c, '&' | '|' | '(' | ')' | ',') {
+                        break;
+                    }
+                    chars.next_if_eq(&(index, c));
+                    let c = match c {
+                        '\\' => chars.next().map_or(c, |(_, c)| c),
+                        c => c,
+                    };
+                    text.push(c);
+                    if !c.is_whitespace() {
+                        end = chars.peek().map_or(query.len(), |(i, _)| *i);
+                    }
+                    next = chars.peek().copied();
+                }
+                tokens.push(Token {
+                    kind: Kind::Term(text.trim_end().to_string()),
+                    span: start..end,
+                });
+                continue;
+            }
+        };
+        tokens.push(Token {
+            kind,
+            span: start..start + c.len_utf8(),
+        });
+    }
+    tokens
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    /// Whether terms that can't be evaluated locally are errors.
+    strict: bool,
+    /// All terms of the query with their position, in order.
+    terms: Vec<(Term, Range<usize>)>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(query: &'a str, strict: bool) -> Parser<'a> {
+        Parser {
+            query,
+            tokens: lex(query),
+            position: 0,
+            strict,
+            terms: Vec::new(),
+        }
+    }
+
+    fn parse(&mut self) -> Result<Query, Error> {
+        let result = self.or()?;
+        match self.next() {
+            Some(token) if token.kind == Kind::Comma => Err(Error::new(
+                "only a single query can be used here",
+                token.span,
+            )),
+            Some(token) => Err(Error::new(
+                format!("unexpected `{}`", &self.query[token.span.clone()]),
+                token.span,
+            )),
+            None => Ok(result),
+        }
+    }
+}
+
+pub fn parse(query: &str) -> Result<Query, Error> {
+    Parser::new(query, true).parse()
+}
+
+/// Checks all comma separated queries of the filter.
+pub fn check(query: &str) -> Result<Vec<(Term, Range<usize>)>, Error> {
+    let mut parser = Parser::new(query, false);
+    loop {
+        parser.or()?;
+        if !parser.eat(Kind::Comma) {
+            break;
+        }
+    }
+    if let Some(token) = parser.next() {
+        return Err(Error::new(
+            format!("unexpected `{}`", &query[token.span.clone()]),
+            token.span,
+        ));
+    }
+    Ok(parser.terms)
+}
+
+/// Splits a filter into its comma separated queries, keeping escaped commas.
+pub fn split(query: &str) -> Vec<&str> {
+    let mut start = 0;
+    let mut queries = Vec::new();
+    for token in lex(query) {
+        if token.kind == Kind::Comma {
+            queries.push(query[start..token.span.start].trim());
+            start = token.span.end;
+        }
+    }
+    queries.push(query[start..].trim());
+    queries
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, kind: Kind) -> bool {
+        let found = self
+            .tokens
+            .get(self.position)
+            .is_some_and(|t| t.kind == kind);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn end(&self) -> Range<usize> {
+        self.query.len()..self.query.len()
+    }
+
+    fn or(&mut self) -> Result<Query, Error> {
+        let mut query = self.and()?;
+        while self.eat(Kind::Or) {
+            query = Query::Or(Box::new(query), Box::new(self.and()?));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, Error> {
+        let mut query = self.unary()?;
+        while self.eat(Kind::And) {
+            query = Query::And(Box::new(query), Box::new(self.unary()?));
+        }
+        Ok(query)
+    }
+
+    fn unary(&mut self) -> Result<Query, Error> {
+        let Some(token) = self.next() else {
+            return Err(Error::new("expected a filter", self.end()));
+        };
+        match token.kind {
+            Kind::Not => Ok(Query::Not(Box::new(self.unary()?))),
+            Kind::Open => {
+                let query = self.or()?;
+                if !self.eat(Kind::Close) {
+                    let span = self
+                        .tokens
+                        .get(self.position)
+                        .map_or_else(|| self.end(), |t| t.span.clone());
+                    return Err(Error::new("expected `)`", span));
+                }
+                Ok(query)
+            }
+            Kind::Term(text) => {
+                let term = term(&text, token.span.clone(), self.strict)?;
+                self.terms.push((term.clone(), token.span));
+                Ok(Query::Term(term))
+            }
+            _ => Err(Error::new(
+                format!(
+                    "expected a filter before `{}`",
+                    &self.query[token.span.clone()]
+                ),
+                token.span,
+            )),
+        }
+    }
+}
+
+/// Strips a keyword like `search:` from the start of the text, ignoring case.
+fn keyword<'t>(text: &'t str, keyword: &str) -> Option<&'t str> {
+    text.get(..keyword.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(keyword))
+        .map(|_| text[keyword.len()..].trim())
+}
+
+/// Parses a single term. Unless `strict` is set, terms that are unknown here are kept as
+/// [`Term::Unknown`], as the Todoist API might still understand them.
+fn term(text: &str, span: Range<usize>, strict: bool) -> Result<Term, Error> {
+    let name = |name: &str, kind: &str| {
+        let name = name.trim();
+        if name.is_empty() {
+            Err(Error::new(format!("missing {kind} name"), span.clone()))
+        } else {
+            Ok(name.to_string())
+        }
+    };
+    let day = |value: &str, term: fn(Day) -> Term| match date(value) {
+        Some(day) => Ok(term(day)),
+        None if strict => Err(Error::new(format!("unknown date `{value}`"), span.clone())),
+        None => Ok(Term::Unknown(text.to_string())),
+    };
+    if let Some(project) = text.strip_prefix("##") {
+        return Ok(Term::Project {
+            name: name(project, "project")?,
+            subprojects: true,
+        });
+    }
+    if let Some(project) = text.strip_prefix('#') {
+        return Ok(Term::Project {
+            name: name(project, "project")?,
+            subprojects: false,
+        });
+    }
+    if let Some(label) = text.strip_prefix('@') {
+        return Ok(Term::Label(name(label, "label")?));
+    }
+    if let Some(section) = text.strip_prefix('/') {
+        return Ok(Term::Section(name(section, "section")?));
+    }
+    if let Some(search) = keyword(text, "search:") {
+        return Ok(Term::Search(name(search, "search")?));
+    }
+    if let Some(value) = keyword(text, "due before:") {
+        return day(value, Term::DueBefore);
+    }
+    if let Some(value) = keyword(text, "due after:") {
+        return day(value, Term::DueAfter);
+    }
+    if let Some(value) = keyword(text, "due:") {
+        return day(value, Term::Due);
+    }
+    let normalized = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    let term = match normalized.as_str() {
+        "all" => Term::All,
+        "overdue" | "od" => Term::Overdue,
+        "no date" | "no due date" => Term::NoDate,
+        "recurring" => Term::Recurring,
+        "no labels" => Term::NoLabels,
+        "subtask" => Term::Subtask,
+        "p1" => Term::Priority(1),
+        "p2" => Term::Priority(2),
+        "p3" => Term::Priority(3),
+        "p4" => Term::Priority(4),
+        days => match days
+            .trim_start_matches("next ")
+            .strip_suffix(" days")
+            .and_then(|n| n.parse().ok())
+        {
+            Some(days) => Term::Days(days),
+            None => match date(&normalized) {
+                Some(day) => Term::Due(day),
+                None if strict => {
+                    return Err(Error::new(format!("unknown filter `{text}`"), span));
+                }
+                None => Term::Unknown(text.to_string()),
+            },
+        },
+    };
+    Ok(term)
+}
+
+/// Parses dates like `today`, `+3 days`, `2022-09-01`, `Sep 1` or `1 September 2022`.
+fn date(text: &str) -> Option<Day> {
+    let text = text
+        .replace(',', " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    match text.as_str() {
+        "today" => return Some(Day::Relative(0)),
+        "tomorrow" => return Some(Day::Relative(1)),
+        "yesterday" => return Some(Day::Relative(-1)),
+        _ => {}
+    }
+    let relative = text.strip_prefix("in ").unwrap_or(&text);
+    if let Some(days) = relative
+        .strip_suffix(" days")
+        .or_else(|| relative.strip_suffix(" day"))
+        .and_then(|n| n.trim_start_matches('+').parse().ok())
+    {
+        return Some(Day::Relative(days));
+    }
+    for format in ["%Y-%m-%d", "%b %d %Y", "%d %b %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&text, format) {
+            return Some(Day::Date(date));
+        }
+    }
+    // Parses with a leap year, so that Feb 29 is a valid day as well.
+    for format in ["%b %d %Y", "%d %b %Y"] {
+        if let Ok(date) = NaiveDate::parse_from_str(&format!("{text} 2000"), format) {
+            return Some(Day::ThisYear {
+                month: date.month(),
+                day: date.day(),
+            });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn term(term: Term) -> Box<Query> {
+        Box::new(Query::Term(term))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("today | !overdue & @home").unwrap(),
+            Query::Or(
+                term(Term::Due(Day::Relative(0))),
+                Box::new(Query::And(
+                    Box::new(Query::Not(term(Term::Overdue))),
+                    term(Term::Label("home".to_string()))
+                ))
+            )
+        );
+        assert_eq!(
+            parse("(today | overdue) & ##Work").unwrap(),
+            Query::And(
+                Box::new(Query::Or(
+                    term(Term::Due(Day::Relative(0))),
+                    term(Term::Overdue)
+                )),
+                term(Term::Project {
+                    name: "Work".to_string(),
+                    subprojects: true
+                })
+            )
+        );
+    }
+
+    #[test]
+    fn terms() {
+        let parsed = |query| match parse(query).unwrap() {
+            Query::Term(term) => term,
+            query => panic!("{query:?} is not a term"),
+        };
+        assert_eq!(parsed("P1"), Term::Priority(1));
+        assert_eq!(parsed("no  date"), Term::NoDate);
+        assert_eq!(parsed("next 7 days"), Term::Days(7));
+        assert_eq!(
+            parsed("#Project \\(Old\\)"),
+            Term::Project {
+                name: "Project (Old)".to_string(),
+                subprojects: false
+            }
+        );
+        assert_eq!(
+            parsed("/Section One"),
+            Term::Section("Section One".to_string())
+        );
+        assert_eq!(
+            parsed("search: Meeting notes"),
+            Term::Search("Meeting notes".to_string())
+        );
+        assert_eq!(
+            parsed("due before: +3 days"),
+            Term::DueBefore(Day::Relative(3))
+        );
+        assert_eq!(
+            parsed("due before: in 1 day"),
+            Term::DueBefore(Day::Relative(1))
+        );
+        assert_eq!(
+            parsed("due after: 2022-09-01"),
+            Term::DueAfter(Day::Date("2022-09-01".parse().unwrap()))
+        );
+        assert_eq!(
+            parsed("Sep 1"),
+            Term::Due(Day::ThisYear { month: 9, day: 1 })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse("today & tomorow").unwrap_err(),
+            Error::new("unknown filter `tomorow`", 8..15)
+        );
+        assert_eq!(
+            parse("(today | od").unwrap_err(),
+            Error::new("expected `)`", 11..11)
+        );
+        assert_eq!(
+            parse("today & | od").unwrap_err(),
+            Error::new("expected a filter before `|`", 8..9)
+        );
+        assert_eq!(
+            parse("today )").unwrap_err(),
+            Error::new("unexpected `)`", 6..7)
+        );
+        assert_eq!(
+            parse("due before: someday").unwrap_err(),
+            Error::new("unknown date `someday`", 0..19)
+        );
+        assert_eq!(
+            parse("").unwrap_err(),
+            Error::new("expected a filter", 0..0)
+        );
+    }
+
+    #[test]
+    fn multiple_queries() {
+        assert_eq!(
+            split("today, overdue & #A\\,B ,@waiting"),
+            vec!["today", "overdue & #A\\,B", "@waiting"]
+        );
+        assert_eq!(split("today"), vec!["today"]);
+        assert_eq!(check("today, @waiting").unwrap().len(), 2);
+        assert_eq!(
+            check("today, ").unwrap_err(),
+            Error::new("expected a filter", 7..7)
+        );
+        assert_eq!(
+            parse("today, @waiting").unwrap_err(),
+            Error::new("only a single query can be used here", 5..6)
+        );
+    }
+
+    #[test]
+    fn check_terms() {
+        assert_eq!(
+            check("assigned to: me & (@home | due before: next week)").unwrap(),
+            vec![
+                (Term::Unknown("assigned to: me".to_string()), 0..15),
+                (Term::Label("home".to_string()), 19..24),
+                (Term::Unknown("due before: next week".to_string()), 27..48),
+            ]
+        );
+        assert_eq!(
+            check("today & (# | p1)").unwrap_err(),
+            Error::new("missing project name", 9..10)
+        );
+    }
+}
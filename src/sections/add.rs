@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{CreateSection, Gateway, Project},
+    config::Config,
     interactive,
 };
 use color_eyre::Result;
@@ -12,9 +13,11 @@ pub struct Params {
     name: String,
 }
 
-pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
     let section = gw
         .create_section(&CreateSection {
             name: params.name,
@@ -22,6 +25,9 @@ pub async fn add(params: Params, gw: &Gateway) -> Result<()> {
             ..Default::default()
         })
         .await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(&section);
+    }
     println!("created section: {}", &section);
     Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{Gateway, Section},
+    config::Config,
     interactive,
 };
 use color_eyre::Result;
@@ -11,10 +12,15 @@ pub struct Params {
     section: interactive::Selection<Section>,
 }
 
-pub async fn delete(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn delete(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let sections = gw.sections().await?;
-    let section = params.section.mandatory(&sections)?;
+    let section = params
+        .section
+        .mandatory(&sections, interactive::Ambiguity::new(cfg.output.is_text()))?;
     gw.delete_section(&section.id).await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(section);
+    }
     println!("deleted section: {}", &section);
     Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::{
     api::rest::{Gateway, Project},
+    config::Config,
     interactive,
 };
 use color_eyre::Result;
@@ -11,15 +12,20 @@ pub struct Params {
 }
 
 /// Lists available sections in a project.
-pub async fn list(params: Params, gw: &Gateway) -> Result<()> {
+pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let projects = gw.projects().await?;
-    let project = params.project.mandatory(&projects)?;
+    let project = params
+        .project
+        .mandatory(&projects, interactive::Ambiguity::new(cfg.output.is_text()))?;
     let sections = gw
         .sections()
         .await?
         .into_iter()
         .filter(|s| s.project_id == project.id)
         .collect::<Vec<_>>();
+    if !cfg.output.is_text() {
+        return cfg.output.list(&sections);
+    }
     println!("{project} sections:");
     for s in sections {
         println!("{s}");
@@ -1,22 +1,54 @@
-use std::collections::HashMap;
+use std::{fs, path::PathBuf};
 
-use color_eyre::Result;
+use chrono::Utc;
+use color_eyre::{Result, eyre::WrapErr};
 
 use crate::{
     api::{
-        rest::{CreateTask, Gateway, Label, Project, Section, TableTask, TaskDue},
+        rest::{
+            CreateTask, Gateway, Label, Project, ResolvedTask, Section, TableTask, Task, TaskID,
+        },
         tree::Tree,
     },
     config::Config,
     interactive,
     labels::{self, LabelSelect},
-    tasks::Priority,
+    tasks::{
+        Priority, bulk,
+        filter::TaskOrInteractive,
+        inline::{Defaults, Inline},
+    },
 };
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
     /// Name (title) of the task to add to the todo list.
-    name: String,
+    ///
+    /// The name can set the project and section with `#project/section`, labels with `@label`
+    /// and the priority with `p1`. Any other words after those are used as the due date.
+    ///
+    /// Example: "Write report #Work/Q3 @deep p1 tomorrow 9am"
+    #[arg(required_unless_present = "from")]
+    name: Option<String>,
+    /// Uses the name as it is, without looking for projects, labels and other details in it.
+    #[arg(short = 'r', long = "raw")]
+    raw: bool,
+    /// Shows the task that would be created without creating it.
+    #[arg(long = "preview", conflicts_with = "from")]
+    preview: bool,
+    /// Makes the task a subtask of the task with this ID. Selects the parent task interactively
+    /// if no ID is given.
+    #[arg(long = "parent", value_name = "ID")]
+    parent: Option<Option<TaskID>>,
+    /// Adds a subtask with this name under the new task. Can be used multiple times.
+    #[arg(long = "sub", value_name = "NAME", conflicts_with = "from")]
+    sub: Vec<String>,
+    /// Adds one task per line of the file, or stdin if set to `-`. Indented lines become
+    /// subtasks, and lines are read the same way as the name.
+    ///
+    /// Other options are used as defaults for lines that don't set them.
+    #[arg(long = "from", conflicts_with = "name")]
+    from: Option<PathBuf>,
     /// Set due with a human-readable text.
     ///
     /// Examples: "in two days" "tomorrow", "every 2 days from Monday"
@@ -38,54 +70,165 @@ pub struct Params {
 
 pub async fn add(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let (projects, sections) = tokio::try_join!(gw.projects(), gw.sections())?;
-    let project = params.project.optional(&projects)?;
-    let section = params.section.optional(&sections)?;
+    let ambiguity = interactive::Ambiguity::new(cfg.output.is_text());
+    let project = params.project.optional(&projects, ambiguity)?;
+    let section = params.section.optional(&sections, ambiguity)?;
+    let all_labels = gw.labels().await?;
     let labels = params
         .labels
-        .labels(&gw.labels().await?, labels::Selection::AllowEmpty)?;
-    let mut create = CreateTask {
-        content: params.name,
+        .labels(&all_labels, labels::Selection::AllowEmpty, ambiguity)?;
+    let defaults = Defaults {
+        project,
+        section,
+        labels: &labels,
+        priority: params.priority,
+        due: params.due,
         description: params.desc,
-        priority: params.priority.map(|p| p.into()),
-        project_id: project.map(|p| p.id.clone()),
-        section_id: section.map(|s| s.id.clone()),
-        labels: labels.iter().map(|l| l.name.clone()).collect(),
-        ..Default::default()
     };
-    if let Some(due) = params.due {
-        create.due = Some(TaskDue::String(due));
+    if let Some(from) = params.from {
+        let text = if from.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin()).wrap_err("unable to read stdin")?
+        } else {
+            fs::read_to_string(&from)
+                .wrap_err_with(|| format!("unable to read {}", from.display()))?
+        };
+        return bulk::add(&text, defaults, &projects, &sections, &all_labels, gw, cfg).await;
     }
-    let labels = if !create.labels.is_empty() {
-        let mut labels: HashMap<_, _> = gw
-            .labels()
-            .await?
-            .into_iter()
-            .map(|label| (label.name.clone(), label))
-            .collect();
-        create
-            .labels
-            .iter()
-            .filter_map(|l| labels.remove(l))
-            .collect()
-    } else {
-        Vec::new()
+    let parent = match params.parent {
+        Some(Some(id)) => Some(id),
+        Some(None) => Some(TaskOrInteractive::interactive().task(gw, cfg).await?.0),
+        None => None,
     };
-    create_task(create, project, section, &labels, gw, cfg).await
+    let parse = |name: String| {
+        if params.raw {
+            Inline {
+                content: name,
+                ..Default::default()
+            }
+        } else {
+            Inline::parse(&name)
+        }
+    };
+    let mut create = parse(params.name.unwrap_or_default()).resolve(
+        parent.is_some(),
+        &defaults,
+        &projects,
+        &sections,
+        &all_labels,
+        ambiguity,
+    )?;
+    create.parent_id = parent;
+    // Subtasks only get what is set in their own name, everything else comes from the parent.
+    let subtask_defaults = Defaults {
+        project: None,
+        section: None,
+        labels: &[],
+        priority: None,
+        due: None,
+        description: None,
+    };
+    let subtasks = params
+        .sub
+        .into_iter()
+        .map(|name| {
+            parse(name).resolve(
+                true,
+                &subtask_defaults,
+                &projects,
+                &sections,
+                &all_labels,
+                ambiguity,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if params.preview {
+        let now = cfg.override_time.unwrap_or_else(Utc::now);
+        let parent = create.preview(&now);
+        let mut tasks = subtasks
+            .iter()
+            .map(|subtask| {
+                let mut task = subtask.preview(&now);
+                if subtask.project_id.is_none() {
+                    task.project_id = parent.project_id.clone();
+                    task.section_id = parent.section_id.clone();
+                }
+                let mut task = Tree::new(task);
+                task.depth = 1;
+                task
+            })
+            .collect::<Vec<_>>();
+        tasks.insert(0, Tree::new(parent));
+        return print_tasks(
+            "would create task",
+            &tasks,
+            &projects,
+            &sections,
+            &all_labels,
+            cfg,
+        );
+    }
+    create_task(create, subtasks, &projects, &sections, &all_labels, gw, cfg).await
 }
 
+/// Creates the task followed by its subtasks and prints everything that was created.
 pub(super) async fn create_task(
     create: CreateTask,
-    project: Option<&Project>,
-    section: Option<&Section>,
+    subtasks: Vec<CreateTask>,
+    projects: &[Project],
+    sections: &[Section],
     labels: &[Label],
     gw: &Gateway,
     cfg: &Config,
 ) -> Result<()> {
     let task = Tree::new(gw.create(&create).await?);
-    let mut table = TableTask::from_task(&task, cfg);
-    table.1 = project;
-    table.2 = section;
-    table.3 = labels.iter().collect();
-    println!("created task: {table}");
+    let mut tasks = vec![task];
+    for mut subtask in subtasks {
+        subtask.parent_id = Some(tasks[0].id.clone());
+        let mut task = Tree::new(gw.create(&subtask).await?);
+        task.depth = 1;
+        tasks.push(task);
+    }
+    print_tasks("created task", &tasks, projects, sections, labels, cfg)
+}
+
+fn print_tasks(
+    prefix: &str,
+    tasks: &[Tree<Task>],
+    projects: &[Project],
+    sections: &[Section],
+    labels: &[Label],
+    cfg: &Config,
+) -> Result<()> {
+    let project = |task: &Task| projects.iter().find(|p| p.id == task.project_id);
+    let section = |task: &Task| {
+        task.section_id
+            .as_ref()
+            .and_then(|id| sections.iter().find(|s| s.id == *id))
+    };
+    if !cfg.output.is_text() {
+        let resolved = tasks
+            .iter()
+            .map(|task| ResolvedTask {
+                task,
+                project_name: project(task).map(|p| p.name.as_str()),
+                section_name: section(task).map(|s| s.name.as_str()),
+                comments: None,
+            })
+            .collect::<Vec<_>>();
+        return match resolved.as_slice() {
+            [task] => cfg.output.item(task),
+            tasks => cfg.output.list(tasks),
+        };
+    }
+    for task in tasks {
+        let mut table = TableTask::from_task(task, cfg);
+        table.1 = project(task);
+        table.2 = section(task);
+        table.3 = labels
+            .iter()
+            .filter(|l| task.labels.contains(&l.name))
+            .collect();
+        println!("{prefix}: {table}");
+    }
     Ok(())
 }
@@ -0,0 +1,206 @@
+use chrono::{Days, NaiveDate, Utc};
+use color_eyre::Result;
+use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
+
+use crate::{
+    api::{
+        rest::{Gateway, ResolvedTask, Task},
+        tree::{Tree, TreeFlattenExt},
+    },
+    config::Config,
+};
+
+use super::state::State;
+
+/// Number of days shown in the agenda if nothing else is specified.
+pub const DEFAULT_DAYS: u64 = 7;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Number of days to show, starting with today. Overdue tasks are always shown.
+    #[arg(short = 'd', long = "days", default_value_t = DEFAULT_DAYS)]
+    pub days: u64,
+}
+
+/// The tasks that are due on a day of the agenda.
+struct Day {
+    title: String,
+    /// Not set for overdue tasks, which can be due on any day before today.
+    date: Option<NaiveDate>,
+    tasks: Vec<Tree<Task>>,
+}
+
+/// Used to serialize a [`Day`] with its tasks.
+#[derive(Serialize)]
+struct AgendaDay<'a> {
+    title: &'a str,
+    date: Option<NaiveDate>,
+    tasks: Vec<ResolvedTask<'a>>,
+}
+
+/// Shows tasks with due dates for the next days, grouped by the day they're due on.
+pub async fn agenda(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = State::fetch_tree(Some("all"), gw, cfg).await?;
+    let today = cfg.override_time.unwrap_or_else(Utc::now).date_naive();
+    let days = days(
+        state.tasks.flat_tree().into_iter().map(|t| &t.item),
+        today,
+        params.days,
+    );
+    if !cfg.output.is_text() {
+        return cfg.output.list(
+            &days
+                .iter()
+                .map(|day| AgendaDay {
+                    title: &day.title,
+                    date: day.date,
+                    tasks: day.tasks.iter().map(|t| state.resolved_task(t)).collect(),
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    if days.is_empty() {
+        println!("No tasks due in the next {} days", params.days);
+    }
+    for (index, day) in days.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        if day.date.is_none() {
+            println!(
+                "{}",
+                day.title
+                    .if_supports_color(Stream::Stdout, |text| text.bright_red())
+            );
+        } else {
+            println!(
+                "{}",
+                day.title
+                    .if_supports_color(Stream::Stdout, |text| text.bold())
+            );
+        }
+        for task in &day.tasks {
+            println!("{}", state.row(task));
+        }
+    }
+    Ok(())
+}
+
+/// Groups the tasks into overdue tasks and the given number of days starting from today, leaving
+/// out days without tasks. Tasks with an exact time come first within a day, in order of time.
+fn days<'a>(tasks: impl Iterator<Item = &'a Task>, today: NaiveDate, count: u64) -> Vec<Day> {
+    let mut tasks = tasks
+        .filter(|t| t.due.is_some())
+        .cloned()
+        .collect::<Vec<_>>();
+    tasks.sort_by(|a, b| {
+        let (a_due, b_due) = (a.due.as_ref().unwrap(), b.due.as_ref().unwrap());
+        a_due
+            .date
+            .cmp(&b_due.date)
+            .then_with(|| a_due.exact.is_none().cmp(&b_due.exact.is_none()))
+            .then_with(|| {
+                let datetime = |t: &Task| {
+                    t.due
+                        .as_ref()
+                        .and_then(|d| d.exact.as_ref())
+                        .map(|e| e.datetime)
+                };
+                datetime(a).cmp(&datetime(b))
+            })
+            .then_with(|| a.cmp(b))
+    });
+    let mut days = Vec::new();
+    let overdue = tasks
+        .iter()
+        .filter(|t| t.due.as_ref().unwrap().date < today)
+        .cloned()
+        .map(Tree::new)
+        .collect::<Vec<_>>();
+    if !overdue.is_empty() {
+        days.push(Day {
+            title: "Overdue".to_string(),
+            date: None,
+            tasks: overdue,
+        });
+    }
+    for offset in 0..count {
+        let Some(date) = today.checked_add_days(Days::new(offset)) else {
+            break;
+        };
+        let due = tasks
+            .iter()
+            .filter(|t| t.due.as_ref().unwrap().date == date)
+            .cloned()
+            .map(Tree::new)
+            .collect::<Vec<_>>();
+        if due.is_empty() {
+            continue;
+        }
+        days.push(Day {
+            title: match offset {
+                0 => "Today".to_string(),
+                1 => "Tomorrow".to_string(),
+                _ => date.format("%A %b %-d").to_string(),
+            },
+            date: Some(date),
+            tasks: due,
+        });
+    }
+    days
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::DateTime;
+
+    use crate::api::rest::{DueDate, ExactTime, Priority};
+
+    use super::*;
+
+    fn due(id: &str, date: &str, time: Option<&str>) -> Task {
+        Task {
+            due: Some(DueDate {
+                string: date.to_string(),
+                date: date.parse().unwrap(),
+                is_recurring: false,
+                exact: time.map(|time| ExactTime {
+                    datetime: DateTime::parse_from_rfc3339(&format!("{date}T{time}Z")).unwrap(),
+                    timezone: "UTC".to_string(),
+                }),
+            }),
+            ..Task::new(id, id)
+        }
+    }
+
+    #[test]
+    fn groups_by_day() {
+        let tasks = [
+            due("later", "2022-08-26", Some("18:00:00")),
+            Task {
+                priority: Priority::Urgent,
+                ..due("untimed", "2022-08-26", None)
+            },
+            due("earlier", "2022-08-26", Some("08:00:00")),
+            due("overdue", "2022-08-20", None),
+            due("sunday", "2022-08-28", None),
+            due("too late", "2022-09-02", None),
+            Task::new("undated", "undated"),
+        ];
+        let days = days(tasks.iter(), "2022-08-26".parse().unwrap(), 7);
+        assert_eq!(
+            days.iter()
+                .map(|d| (
+                    d.title.as_str(),
+                    d.tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Overdue", vec!["overdue"]),
+                ("Today", vec!["earlier", "later", "untimed"]),
+                ("Sunday Aug 28", vec!["sunday"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Applies an operation to many tasks at once, showing the progress and the result for each task.
+use std::future::Future;
+
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use indicatif::{ProgressBar, ProgressStyle};
+use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use crate::{
+    api::{
+        rest::{Task, TaskID},
+        tree::Tree,
+    },
+    interactive,
+};
+
+use super::state::State;
+
+/// How many requests are sent to the Todoist API at the same time.
+const CONCURRENCY: usize = 4;
+
+/// The result of the operation for a single task, used for machine readable output.
+#[derive(Serialize)]
+struct Outcome<'a> {
+    id: &'a TaskID,
+    content: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Shows the tasks that are about to be changed and asks whether to go ahead with `action`,
+/// unless `yes` is set. Machine readable output can't be combined with a prompt, so `yes` is
+/// required there.
+pub(super) fn confirm(
+    tasks: &[&Tree<Task>],
+    action: &str,
+    yes: bool,
+    state: &State,
+) -> Result<bool> {
+    if yes || tasks.is_empty() {
+        return Ok(true);
+    }
+    if !state.config.output.is_text() {
+        return Err(eyre!(
+            "use --yes to {} tasks without confirmation",
+            action.to_lowercase()
+        ));
+    }
+    for task in tasks {
+        println!("{}", state.row(task));
+    }
+    interactive::confirm(&format!("{action} {} tasks?", tasks.len()))
+}
+
+/// Runs the operation for each task with at most [`CONCURRENCY`] operations at a time, then shows
+/// the result for each task. `done` describes a successful operation, like "updated". Fails if the
+/// operation failed for any of the tasks.
+pub(super) async fn run<F, Fut>(
+    tasks: &[&Tree<Task>],
+    done: &str,
+    state: &State<'_>,
+    operation: F,
+) -> Result<()>
+where
+    F: Fn(&Task) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let cfg = state.config;
+    let bar = if cfg.output.is_text() {
+        ProgressBar::new(tasks.len() as u64)
+    } else {
+        ProgressBar::hidden()
+    };
+    bar.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {wide_msg}")
+            .wrap_err("invalid progress bar template")?,
+    );
+    let mut pending = tasks.iter().enumerate();
+    let mut running = JoinSet::new();
+    let mut results = Vec::with_capacity(tasks.len());
+    loop {
+        while running.len() < CONCURRENCY
+            && let Some((index, task)) = pending.next()
+        {
+            let future = operation(&task.item);
+            running.spawn(async move { (index, future.await) });
+        }
+        let Some(joined) = running.join_next().await else {
+            break;
+        };
+        let (index, result) = joined.wrap_err("operation on task was aborted")?;
+        bar.set_message(tasks[index].content.clone());
+        bar.inc(1);
+        results.push((index, result));
+    }
+    bar.finish_and_clear();
+    // Results are shown in the order of the tasks, not in the order they finished in.
+    results.sort_by_key(|(index, _)| *index);
+
+    let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+    if cfg.output.is_text() {
+        for (index, result) in &results {
+            let row = state.row(tasks[*index]);
+            match result {
+                Ok(()) => println!("{done} {row}"),
+                Err(e) => println!(
+                    "{} {row}: {e:#}",
+                    "failed".if_supports_color(Stream::Stdout, |text| text.bright_red())
+                ),
+            }
+        }
+        println!(
+            "{done} {} of {} tasks",
+            results.len() - failed,
+            results.len()
+        );
+    } else {
+        cfg.output.list(
+            &results
+                .iter()
+                .map(|(index, result)| Outcome {
+                    id: &tasks[*index].id,
+                    content: &tasks[*index].content,
+                    error: result.as_ref().err().map(|e| format!("{e:#}")),
+                })
+                .collect::<Vec<_>>(),
+        )?;
+    }
+    if failed > 0 {
+        return Err(eyre!("{failed} of {} tasks failed", results.len()));
+    }
+    Ok(())
+}
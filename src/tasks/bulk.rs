@@ -0,0 +1,180 @@
+//! Adds many tasks at once from text with one task per line.
+//!
+//! Indented lines become subtasks of the closest line above them with less indentation, and each
+//! line can use the tokens described in [`super::inline`].
+use std::collections::HashMap;
+
+use color_eyre::{Result, eyre::WrapErr};
+use indicatif::{ProgressBar, ProgressStyle};
+
+use crate::{
+    api::{
+        rest::{Gateway, Label, Project, ResolvedTask, Section, TableTask, Task},
+        tree::Tree,
+    },
+    config::Config,
+    interactive::Ambiguity,
+};
+
+use super::inline::{Defaults, Inline};
+
+/// A task of the input and the index of the line it's nested under.
+#[derive(Debug, PartialEq)]
+struct Line {
+    parent: Option<usize>,
+    inline: Inline,
+}
+
+/// Width of a tab when comparing indentation.
+const TAB_WIDTH: usize = 4;
+
+fn parse(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    // Indentation and index of the lines that following lines can be nested under.
+    let mut parents: Vec<(usize, usize)> = Vec::new();
+    for line in text.lines().filter(|l| !l.trim().is_empty()) {
+        let indent = line
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+            .sum();
+        while parents.last().is_some_and(|(i, _)| *i >= indent) {
+            parents.pop();
+        }
+        let content = line.trim();
+        // Allow pasting lists and checklists as they are.
+        let content = ["- [ ] ", "- ", "* "]
+            .iter()
+            .find_map(|marker| content.strip_prefix(marker))
+            .unwrap_or(content);
+        lines.push(Line {
+            parent: parents.last().map(|(_, index)| *index),
+            inline: Inline::parse(content),
+        });
+        parents.push((indent, lines.len() - 1));
+    }
+    lines
+}
+
+/// Creates a task for each line of the text in order, so parents exist before their subtasks.
+pub(super) async fn add(
+    text: &str,
+    defaults: Defaults<'_>,
+    projects: &[Project],
+    sections: &[Section],
+    labels: &[Label],
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<()> {
+    // Resolve everything up front, so that nothing gets created if a line is invalid.
+    let lines = parse(text)
+        .into_iter()
+        .enumerate()
+        .map(|(number, line)| {
+            line.inline
+                .resolve(
+                    line.parent.is_some(),
+                    &defaults,
+                    projects,
+                    sections,
+                    labels,
+                    // Lines can't be clarified one by one, as they might come from stdin.
+                    Ambiguity::Fail,
+                )
+                .wrap_err_with(|| format!("invalid task on line {}", number + 1))
+                .map(|create| (line.parent, create))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let bar = if cfg.output.is_text() {
+        ProgressBar::new(lines.len() as u64)
+    } else {
+        ProgressBar::hidden()
+    };
+    bar.set_style(
+        ProgressStyle::with_template("{bar:40} {pos}/{len} {wide_msg}")
+            .wrap_err("invalid progress bar template")?,
+    );
+    let projects: HashMap<_, _> = projects.iter().map(|p| (&p.id, p)).collect();
+    let sections: HashMap<_, _> = sections.iter().map(|s| (&s.id, s)).collect();
+    let labels: HashMap<_, _> = labels.iter().map(|l| (&l.name, l)).collect();
+    let mut created: Vec<Tree<Task>> = Vec::with_capacity(lines.len());
+    for (parent, mut create) in lines {
+        bar.set_message(create.content.clone());
+        create.parent_id = parent.map(|p| created[p].id.clone());
+        let task = Tree::new(
+            gw.create(&create)
+                .await
+                .wrap_err_with(|| format!("unable to create task '{}'", create.content))?,
+        );
+        if cfg.output.is_text() {
+            let mut table = TableTask::from_task(&task, cfg);
+            table.1 = projects.get(&task.project_id).copied();
+            table.2 = task
+                .section_id
+                .as_ref()
+                .and_then(|s| sections.get(s).copied());
+            table.3 = task
+                .labels
+                .iter()
+                .filter_map(|l| labels.get(l).copied())
+                .collect();
+            bar.println(format!("created task: {table}"));
+        }
+        created.push(task);
+        bar.inc(1);
+    }
+    bar.finish_and_clear();
+
+    if !cfg.output.is_text() {
+        return cfg.output.list(
+            &created
+                .iter()
+                .map(|task| ResolvedTask {
+                    task,
+                    project_name: projects.get(&task.project_id).map(|p| p.name.as_str()),
+                    section_name: task
+                        .section_id
+                        .as_ref()
+                        .and_then(|s| sections.get(s))
+                        .map(|s| s.name.as_str()),
+                    comments: None,
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    println!("created {} tasks", created.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_indentation() {
+        let lines = parse(
+            "Plan launch #Work p1\n\
+             \x20 - Draft post @writing\n\
+             \x20   - [ ] Get review due: friday\n\
+             \n\
+             \x20 Ship it\n\
+             Follow up\n",
+        );
+        assert_eq!(
+            lines
+                .iter()
+                .map(|l| (l.parent, l.inline.content.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (None, "Plan launch"),
+                (Some(0), "Draft post"),
+                (Some(1), "Get review"),
+                (Some(0), "Ship it"),
+                (None, "Follow up"),
+            ]
+        );
+        assert_eq!(lines[0].inline.project.as_deref(), Some("Work"));
+        assert_eq!(lines[2].inline.due.as_deref(), Some("friday"));
+    }
+}
@@ -1,12 +1,18 @@
+use std::collections::HashSet;
+
 use color_eyre::{Result, eyre::WrapErr};
 use owo_colors::{OwoColorize, Stream};
 
 use crate::{
-    api::{self, rest::Gateway},
+    api::{
+        rest::{Gateway, Task, TaskID},
+        tree::{Tree, TreeFlattenExt},
+    },
     config::Config,
+    interactive,
 };
 
-use super::filter;
+use super::{batch, filter};
 
 #[derive(clap::Parser, Debug)]
 pub struct Params {
@@ -17,18 +23,54 @@ pub struct Params {
     /// of the task to "today" and then close it.
     #[arg(short = 'c', long = "complete")]
     pub complete: bool,
+    /// Closes all tasks within the filter, after showing them and asking for confirmation.
+    #[arg(long = "all", conflicts_with = "id")]
+    pub all: bool,
+    /// Selects any number of tasks within the filter to close with checkboxes.
+    #[arg(short = 'm', long = "multiple", conflicts_with_all = ["id", "all"])]
+    pub multiple: bool,
+    /// Closes all tasks without asking for confirmation first.
+    #[arg(short = 'y', long = "yes", requires = "all")]
+    pub yes: bool,
+}
+
+impl Params {
+    pub fn new(id: TaskID, complete: bool) -> Self {
+        Self {
+            task: id.into(),
+            complete,
+            all: false,
+            multiple: false,
+            yes: false,
+        }
+    }
 }
 
 pub async fn close(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let id = params
+    if params.all || params.multiple {
+        return close_many(params, gw, cfg).await;
+    }
+    let (id, state) = params
         .task
-        .task_id(gw, cfg)
+        .task(gw, cfg)
         .await
         .wrap_err("no task selected for closing")?;
     if params.complete {
-        return complete(&id, gw).await;
+        gw.complete(&id).await?;
+    } else {
+        gw.close(&id).await?;
+    }
+    if !cfg.output.is_text() {
+        let task = gw.task(&id).await?;
+        return cfg.output.item(&state.resolved_task(&task));
+    }
+    if params.complete {
+        println!(
+            "completed task {}",
+            id.if_supports_color(Stream::Stdout, |text| text.bright_red())
+        );
+        return Ok(());
     }
-    gw.close(&id).await?;
     println!("closed task {}", id.clone().bright_red());
     let task = gw.task(&id).await?;
     if !task.checked
@@ -43,11 +85,96 @@ pub async fn close(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     Ok(())
 }
 
-pub async fn complete(id: &api::rest::TaskID, gw: &Gateway) -> Result<()> {
-    gw.complete(id).await?;
-    println!(
-        "completed task {}",
-        id.if_supports_color(Stream::Stdout, |text| text.bright_red())
-    );
-    Ok(())
+/// Closes all tasks within the filter, or the ones that are checked in a selection.
+async fn close_many(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let state = params.task.state(gw, cfg).await?;
+    let tasks = state.tasks.flat_tree();
+    let (action, done) = if params.complete {
+        ("Complete", "completed")
+    } else {
+        ("Close", "closed")
+    };
+    let tasks = if params.multiple {
+        let rows = tasks.iter().map(|t| state.row(t)).collect::<Vec<_>>();
+        interactive::multi_select(&format!("Select tasks to {}", action.to_lowercase()), &rows)?
+            .into_iter()
+            .map(|index| tasks[index])
+            .collect()
+    } else {
+        if !batch::confirm(&tasks, action, params.yes, &state)? {
+            println!("No tasks were {done}");
+            return Ok(());
+        }
+        tasks
+    };
+    let (tasks, skipped) = without_subtasks(tasks);
+    let result = batch::run(&tasks, done, &state, |task| {
+        let gw = gw.clone();
+        let id = task.id.clone();
+        let complete = params.complete;
+        async move {
+            if complete {
+                gw.complete(&id).await
+            } else {
+                gw.close(&id).await
+            }
+        }
+    })
+    .await;
+    if skipped > 0 && cfg.output.is_text() {
+        println!("{skipped} subtasks were {done} together with their parent tasks");
+    }
+    result
+}
+
+/// Drops the subtasks of other tasks in the list, as closing a task closes its subtasks as well.
+/// Returns the remaining tasks and the number of dropped subtasks.
+fn without_subtasks(tasks: Vec<&Tree<Task>>) -> (Vec<&Tree<Task>>, usize) {
+    let subtasks = tasks
+        .iter()
+        .flat_map(|task| task.flatten().into_iter().skip(1))
+        .map(|task| &task.id)
+        .collect::<HashSet<_>>();
+    let count = tasks.len();
+    let tasks = tasks
+        .into_iter()
+        .filter(|task| !subtasks.contains(&task.id))
+        .collect::<Vec<_>>();
+    let skipped = count - tasks.len();
+    (tasks, skipped)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drops_subtasks() {
+        let tasks = Tree::from_items(vec![
+            Task::new("1", "Trip"),
+            Task {
+                parent_id: Some("1".to_string()),
+                ..Task::new("2", "Pack")
+            },
+            Task {
+                parent_id: Some("2".to_string()),
+                ..Task::new("3", "Shoes")
+            },
+            Task::new("4", "Laundry"),
+        ])
+        .unwrap();
+        let flat = tasks.flat_tree();
+
+        let (kept, skipped) = without_subtasks(flat.clone());
+        assert_eq!(
+            kept.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(),
+            vec!["1", "4"]
+        );
+        assert_eq!(skipped, 2);
+
+        // Subtasks of tasks that are not closed stay.
+        let (kept, skipped) = without_subtasks(vec![flat[2], flat[3]]);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(skipped, 0);
+    }
 }
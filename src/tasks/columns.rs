@@ -0,0 +1,101 @@
+use strum::{Display, EnumIter};
+
+use crate::api::rest::Task;
+
+use super::state::State;
+
+/// Columns that can be selected for tabular task output.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq, Display, EnumIter)]
+#[strum(serialize_all = "lowercase")]
+pub enum Column {
+    Id,
+    Content,
+    Project,
+    Section,
+    Labels,
+    Priority,
+    Due,
+    Url,
+}
+
+impl Column {
+    /// Returns the value of this column for the given task, using the state to resolve names.
+    pub fn value(&self, task: &Task, state: &State) -> String {
+        let resolved = state.resolved_task(task);
+        match self {
+            Column::Id => task.id.clone(),
+            Column::Content => task.content.clone(),
+            Column::Project => resolved.project_name.unwrap_or_default().to_string(),
+            Column::Section => resolved.section_name.unwrap_or_default().to_string(),
+            Column::Labels => task.labels.join(","),
+            Column::Priority => task.priority.label().to_string(),
+            Column::Due => task
+                .due
+                .as_ref()
+                .map(|due| match &due.exact {
+                    Some(exact) => exact.datetime.to_rfc3339(),
+                    None => due.date.to_string(),
+                })
+                .unwrap_or_default(),
+            Column::Url => task.url.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use strum::IntoEnumIterator;
+
+    use super::*;
+    use crate::{
+        api::{
+            rest::{DueDate, Project, Section},
+            tree::Tree,
+        },
+        config::Config,
+    };
+
+    #[test]
+    fn values() {
+        let cfg = Config::default();
+        let task = Task {
+            project_id: "1".to_string(),
+            section_id: Some("2".to_string()),
+            labels: vec!["one".to_string(), "two".to_string()],
+            priority: crate::api::rest::Priority::Urgent,
+            due: Some(DueDate {
+                string: "tomorrow".to_string(),
+                date: "2022-08-27".parse().unwrap(),
+                is_recurring: false,
+                exact: None,
+            }),
+            ..Task::new("123", "hello")
+        };
+        let state = State {
+            tasks: vec![Tree::new(task.clone())],
+            projects: HashMap::from([("1".to_string(), Project::new("1", "project"))]),
+            sections: HashMap::from([("2".to_string(), Section::new("2", "1", "section"))]),
+            labels: HashMap::new(),
+            config: &cfg,
+            template: None,
+            default_template: Default::default(),
+        };
+        assert_eq!(
+            Column::iter()
+                .map(|c| c.value(&task, &state))
+                .collect::<Vec<_>>(),
+            vec![
+                "123",
+                "hello",
+                "project",
+                "section",
+                "one,two",
+                "p1",
+                "2022-08-27",
+                "http://localhost/"
+            ]
+        );
+    }
+}
@@ -24,6 +24,9 @@ pub async fn comment(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
             content: params.content,
         })
         .await?;
+    if !cfg.output.is_text() {
+        return cfg.output.item(&comment);
+    }
     println!("created comment: {}", FullComment(&comment));
     Ok(())
 }
@@ -21,6 +21,7 @@ enum Selection {
     Description = 2,
     Project = 3,
     Priority = 4,
+    Subtasks = 5,
 }
 
 impl std::fmt::Display for Selection {
@@ -34,6 +35,7 @@ impl std::fmt::Display for Selection {
                 Selection::Description => "Description",
                 Selection::Project => "Project",
                 Selection::Priority => "Priority",
+                Selection::Subtasks => "Subtasks",
             }
         )
     }
@@ -47,6 +49,7 @@ impl From<usize> for Selection {
             2 => Selection::Description,
             3 => Selection::Project,
             4 => Selection::Priority,
+            5 => Selection::Subtasks,
             _ => panic!("bad selection input"),
         }
     }
@@ -60,6 +63,7 @@ pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
 
     let (projects, sections) = tokio::try_join!(gw.projects(), gw.sections())?;
     let mut due: Option<String> = None;
+    let mut subtasks: Vec<String> = Vec::new();
     loop {
         let mut items = vec![format!("{}", "Submit".bold().bright_blue())];
         items.extend(
@@ -92,6 +96,7 @@ pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
                     Selection::Priority,
                     create.priority.unwrap_or_default().to_string(),
                 ),
+                (Selection::Subtasks, subtasks.join(", ")),
             ]
             .iter()
             .map(|(name, content)| format!("{}: {}", name.bold(), content)),
@@ -117,10 +122,23 @@ pub async fn create(_params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
                 };
             }
             Selection::Priority => create.priority = interactive::input_priority()?,
+            Selection::Subtasks => {
+                // Keep asking until an empty name is submitted.
+                while let Some(name) = interactive::input_optional("Subtask", None)? {
+                    subtasks.push(name);
+                }
+            }
         }
     }
     if let Some(due) = due {
         create.due = Some(TaskDue::String(due));
     }
-    create_task(create, None, None, &[], gw, cfg).await
+    let subtasks = subtasks
+        .into_iter()
+        .map(|content| CreateTask {
+            content,
+            ..Default::default()
+        })
+        .collect();
+    create_task(create, subtasks, &projects, &sections, &[], gw, cfg).await
 }
@@ -4,10 +4,12 @@ use crate::{
     api::{
         self,
         rest::{Gateway, TaskDue, UpdateTask},
+        tree::TreeFlattenExt,
     },
     config::Config,
+    interactive,
     labels::{self, LabelSelect},
-    tasks::{Priority, filter::TaskOrInteractive},
+    tasks::{Priority, batch, filter::TaskOrInteractive},
 };
 
 #[derive(clap::Parser, Debug)]
@@ -27,6 +29,17 @@ pub struct Params {
     pub priority: Option<Priority>,
     #[clap(flatten)]
     pub labels: LabelSelect,
+    /// Adds the label with the closest name to the labels the task already has. Can be used
+    /// multiple times to add more labels.
+    #[arg(long = "add-label", value_name = "LABEL")]
+    pub add_labels: Vec<String>,
+    /// Edits all tasks within the filter instead of a single task, after showing them and asking
+    /// for confirmation.
+    #[arg(long = "all", conflicts_with = "id")]
+    pub all: bool,
+    /// Edits all tasks without asking for confirmation first.
+    #[arg(short = 'y', long = "yes", requires = "all")]
+    pub yes: bool,
 }
 
 impl Params {
@@ -38,18 +51,28 @@ impl Params {
             desc: None,
             priority: None,
             labels: LabelSelect::default(),
+            add_labels: Vec::new(),
+            all: false,
+            yes: false,
         }
     }
 }
 
 pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let ambiguity = interactive::Ambiguity::new(cfg.output.is_text());
+    let all_labels = if params.labels.is_empty() && params.add_labels.is_empty() {
+        Vec::new()
+    } else {
+        gw.labels().await?
+    };
     let labels = {
         if params.labels.is_empty() {
             None
         } else {
-            let labels = params
-                .labels
-                .labels(&gw.labels().await?, labels::Selection::AllowEmpty)?;
+            let labels =
+                params
+                    .labels
+                    .labels(&all_labels, labels::Selection::AllowEmpty, ambiguity)?;
             if labels.is_empty() {
                 None
             } else {
@@ -57,6 +80,15 @@ pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
             }
         }
     };
+    let added = params
+        .add_labels
+        .iter()
+        .map(|name| {
+            Ok(interactive::fuzz_select(&all_labels, name, ambiguity)?
+                .name
+                .clone())
+        })
+        .collect::<Result<Vec<_>>>()?;
     let mut update = UpdateTask {
         content: params.name,
         description: params.desc,
@@ -67,13 +99,67 @@ pub async fn edit(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     if let Some(due) = params.due {
         update.due = Some(TaskDue::String(due))
     }
-    if update == UpdateTask::default() {
+    if update == UpdateTask::default() && added.is_empty() {
         return Err(eyre!(
             "No changes to apply. Use the CLI flags to set the desired fields."
         ));
     }
-    gw.update(&params.task.task_id(gw, cfg).await?, &update)
-        .await
+    if params.all {
+        return edit_all(&params.task, params.yes, &update, &added, gw, cfg).await;
+    }
+    let (id, state) = params.task.task(gw, cfg).await?;
+    if !added.is_empty() {
+        let current = match (update.labels.take(), state.task(&id)) {
+            (Some(labels), _) => labels,
+            (None, Some(task)) => task.labels.clone(),
+            (None, None) => gw.task(&id).await?.labels,
+        };
+        update.labels = Some(add_labels(current, &added));
+    }
+    gw.update(&id, &update).await?;
+    if !cfg.output.is_text() {
+        let task = gw.task(&id).await?;
+        return cfg.output.item(&state.resolved_task(&task));
+    }
+    Ok(())
+}
+
+/// Applies the update to all tasks within the filter.
+async fn edit_all(
+    task: &TaskOrInteractive,
+    yes: bool,
+    update: &UpdateTask,
+    added: &[String],
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<()> {
+    let state = task.state(gw, cfg).await?;
+    let tasks = state.tasks.flat_tree();
+    if !batch::confirm(&tasks, "Update", yes, &state)? {
+        println!("No tasks were updated");
+        return Ok(());
+    }
+    batch::run(&tasks, "updated", &state, |task| {
+        let gw = gw.clone();
+        let id = task.id.clone();
+        let mut update = update.clone();
+        if !added.is_empty() {
+            let current = update.labels.take().unwrap_or_else(|| task.labels.clone());
+            update.labels = Some(add_labels(current, added));
+        }
+        async move { gw.update(&id, &update).await }
+    })
+    .await
+}
+
+/// Adds the labels that are not part of the existing labels yet.
+fn add_labels(mut labels: Vec<String>, added: &[String]) -> Vec<String> {
+    for label in added {
+        if !labels.contains(label) {
+            labels.push(label.clone());
+        }
+    }
+    labels
 }
 
 #[cfg(test)]
@@ -82,6 +168,17 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn adds_missing_labels() {
+        assert_eq!(
+            add_labels(
+                vec!["home".to_string(), "two".to_string()],
+                &["two".to_string(), "followup".to_string()]
+            ),
+            vec!["home", "two", "followup"]
+        );
+    }
+
     #[tokio::test]
     async fn update_nochanges() {
         let mock_server = MockServer::start().await;
@@ -94,6 +191,9 @@ mod test {
                 desc: None,
                 priority: None,
                 labels: LabelSelect::default(),
+                add_labels: Vec::new(),
+                all: false,
+                yes: false,
             },
             &gw,
             &Config::default(),
@@ -1,11 +1,22 @@
-use color_eyre::{Result, eyre::eyre};
+use std::ops::Range;
+
+use chrono::Utc;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
 
 use crate::{
-    api::rest::{Gateway, TaskID},
+    api::{
+        rest::{Gateway, TaskID},
+        tree::TreeFlattenExt,
+    },
     config::Config,
+    interactive::{self, Matches},
+    query::{Query, Term},
 };
 
-use super::state::State;
+use super::{refs::Refs, state::State};
 
 #[derive(clap::Parser, Debug)]
 pub struct Filter {
@@ -21,44 +32,77 @@ impl Filter {
     pub fn set_filter(&mut self, filter: Option<&str>) {
         self.filter = filter.map(str::to_string);
     }
-    pub fn select(&self, cfg: &Config) -> String {
-        self.filter
+    /// Returns the filter to use, checking its syntax before it gets sent to the Todoist API.
+    pub fn select(&self, cfg: &Config) -> Result<String> {
+        let filter = self
+            .filter
             .clone()
-            .unwrap_or_else(|| cfg.default_filter.to_owned())
+            .unwrap_or_else(|| cfg.default_filter.to_owned());
+        if !filter.trim().is_empty() {
+            check(&filter)?;
+        }
+        Ok(filter)
     }
 }
 
+/// Checks the syntax of the filter, showing where the problem is if it's invalid.
+pub fn check(filter: &str) -> Result<Vec<(Term, Range<usize>)>> {
+    Query::check(filter).map_err(|e| eyre!("invalid filter\n{}", e.diagnostic(filter)))
+}
+
+/// Parses the filter so that it can be evaluated locally.
+pub fn parse(filter: &str) -> Result<Query> {
+    Query::parse(filter).map_err(|e| eyre!("invalid filter\n{}", e.diagnostic(filter)))
+}
+
 /// TaskOrInteractive is a helper struct to be embedded into other Params so that they can perform
-/// selections based on Task ID or selecting interactively.
+/// selections based on Task ID, the name of a task or selecting interactively.
 #[derive(clap::Parser, Debug)]
 pub struct TaskOrInteractive {
-    /// The Task ID as provided from the Todoist API. Use `list` to find out what ID your task has.
+    /// The Task ID as provided from the Todoist API, the number shown in front of the task by the
+    /// last `list -n`, or a part of the name of a task within the filter, which is fuzzy matched.
     /// If omitted, will interactively select task.
-    id: Option<TaskID>,
+    #[arg(value_name = "TASK")]
+    id: Option<String>,
+    /// Set if `id` is known to be an ID, so it's never matched against task names.
+    #[arg(skip)]
+    exact: bool,
     #[clap(flatten)]
     filter: Filter,
 }
 
 impl TaskOrInteractive {
+    /// Selects the task interactively with the default filter.
+    pub fn interactive() -> Self {
+        Self {
+            id: None,
+            exact: false,
+            filter: Filter::new(None),
+        }
+    }
     pub fn with_id(id: TaskID) -> Self {
         Self {
             id: Some(id),
+            exact: true,
             filter: Filter::new(None),
         }
     }
-    pub async fn task_id(&self, gw: &Gateway, cfg: &Config) -> Result<TaskID> {
-        let (id, _) = self.task(gw, cfg).await?;
-        Ok(id)
+    /// Fetches all tasks within the filter, for commands that work with all of them at once.
+    pub async fn state<'a>(&'_ self, gw: &'_ Gateway, cfg: &'a Config) -> Result<State<'a>> {
+        State::fetch_tree(Some(&self.filter.select(cfg)?), gw, cfg).await
     }
-
     pub async fn task<'a>(
         &'_ self,
         gw: &'_ Gateway,
         cfg: &'a Config,
     ) -> Result<(TaskID, State<'a>)> {
-        let state = State::fetch_tree(Some(&self.filter.select(cfg)), gw, cfg).await?;
+        let state = self.state(gw, cfg).await?;
         let id = match &self.id {
-            Some(id) => id.clone(),
+            Some(id) if self.exact || state.task(id).is_some() => id.clone(),
+            Some(input) => match reference(input, cfg)? {
+                Some(id) => id,
+                None => find(input, &state)?,
+            },
             None => state
                 .select_task()?
                 .map(|t| t.id.clone())
@@ -68,6 +112,49 @@ impl TaskOrInteractive {
     }
 }
 
+/// Returns the ID of the task if the input is a number shown by the last `list -n`.
+fn reference(input: &str, cfg: &Config) -> Result<Option<TaskID>> {
+    let Some(refs) = Refs::load(cfg)? else {
+        return Ok(None);
+    };
+    Ok(refs
+        .resolve(input, cfg.override_time.unwrap_or_else(Utc::now))?
+        .cloned())
+}
+
+/// Finds the task whose name matches, letting the user choose between the candidates if there is
+/// no clear match. Falls back to using the input as an ID if no task matches it and it could be
+/// one, as the ID might belong to a task outside of the filter.
+fn find(name: &str, state: &State) -> Result<TaskID> {
+    let tasks = state
+        .tasks
+        .flat_tree()
+        .into_iter()
+        .map(|t| &t.item)
+        .collect::<Vec<_>>();
+    let candidates = match interactive::fuzz_match(&tasks, name) {
+        Err(_) if !name.is_empty() && !name.contains(char::is_whitespace) => {
+            return Ok(name.to_string());
+        }
+        Err(e) => return Err(e).wrap_err_with(|| format!("no task matching `{name}`")),
+        Ok(Matches::Clear(task)) => return Ok(task.id.clone()),
+        Ok(Matches::Unclear(candidates)) => candidates,
+    };
+    if !state.config.output.is_text() {
+        return Err(interactive::unclear(name, &candidates));
+    }
+    let rows = candidates
+        .iter()
+        .map(|task| match state.task(&task.id) {
+            Some(task) => state.row(task),
+            None => task.content.clone(),
+        })
+        .collect::<Vec<_>>();
+    interactive::select(&format!("No clear match for `{name}`, select task"), &rows)?
+        .map(|index| candidates[index].id.clone())
+        .ok_or_else(|| eyre!("no task selected"))
+}
+
 impl From<TaskID> for TaskOrInteractive {
     fn from(id: TaskID) -> Self {
         Self::with_id(id)
@@ -88,8 +175,18 @@ mod tests {
         };
 
         let f = Filter::new(None);
-        assert!(f.select(&cfg) == *"all");
+        assert!(f.select(&cfg).unwrap() == *"all");
         let f = Filter::new(Some("today".to_owned()));
-        assert!(f.select(&cfg) == *"today");
+        assert!(f.select(&cfg).unwrap() == *"today");
+        let f = Filter::new(Some("(today | overdue".to_owned()));
+        assert_eq!(
+            f.select(&cfg).unwrap_err().to_string(),
+            "invalid filter\n(today | overdue\n                ^ expected `)`"
+        );
+        // Comma separated queries are passed on to the Todoist API as they were before the
+        // syntax was checked.
+        let f = Filter::new(Some("today, overdue & p1".to_owned()));
+        assert!(f.select(&cfg).unwrap() == *"today, overdue & p1");
+        assert_eq!(super::check("today, overdue & p1").unwrap().len(), 3);
     }
 }
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use crate::api::{rest::Task, tree::Tree};
+
+use super::state::State;
+
+/// Fields that task lists can be grouped by.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupBy {
+    Project,
+    Section,
+    Label,
+    Priority,
+    Due,
+    Assignee,
+}
+
+/// A heading in a grouped task list with the tasks under it.
+pub struct Group<'a> {
+    pub title: String,
+    pub tasks: Vec<&'a Tree<Task>>,
+}
+
+impl Group<'_> {
+    /// Number of tasks in the group, including subtasks.
+    pub fn count(&self) -> usize {
+        self.tasks.iter().map(|t| t.flatten().len()).sum()
+    }
+}
+
+impl GroupBy {
+    /// Returns the keys of the groups the task belongs to. The first part of the key orders the
+    /// groups, the second part is the title.
+    ///
+    /// Only tasks with multiple labels can be in more than one group.
+    fn keys(&self, task: &Task, state: &State) -> Vec<(i64, String)> {
+        let resolved = state.resolved_task(task);
+        match self {
+            GroupBy::Project => vec![match state.projects.get(&task.project_id) {
+                Some(project) => (!project.inbox_project as i64, project.name.clone()),
+                None => (1, task.project_id.clone()),
+            }],
+            GroupBy::Section => vec![(
+                0,
+                match resolved.section_name {
+                    Some(section) => {
+                        format!("{}/{}", resolved.project_name.unwrap_or_default(), section)
+                    }
+                    None => resolved.project_name.unwrap_or_default().to_string(),
+                },
+            )],
+            GroupBy::Label if task.labels.is_empty() => vec![(i64::MAX, "(No label)".to_string())],
+            GroupBy::Label => task
+                .labels
+                .iter()
+                .map(|name| {
+                    let order = state.labels.get(name).map_or(0, |l| l.order as i64);
+                    (order, format!("@{name}"))
+                })
+                .collect(),
+            GroupBy::Priority => vec![(-(task.priority as i64), task.priority.label().to_string())],
+            GroupBy::Due => vec![match &task.due {
+                Some(due) => (
+                    due.date
+                        .signed_duration_since(chrono::NaiveDate::MIN)
+                        .num_days(),
+                    due.date.format("%a %b %-d %Y").to_string(),
+                ),
+                None => (i64::MAX, "(No due date)".to_string()),
+            }],
+            GroupBy::Assignee => vec![match &task.responsible_uid {
+                // Only the ID of the assigned user is known, as collaborators aren't fetched.
+                Some(uid) => (0, format!("Assigned to user ID {uid}")),
+                None => (i64::MAX, "(Unassigned)".to_string()),
+            }],
+        }
+    }
+
+    /// Groups the tasks in order of their groups, keeping the order of tasks within a group.
+    pub fn groups<'a>(&self, tasks: &'a [Tree<Task>], state: &State) -> Vec<Group<'a>> {
+        let mut groups: BTreeMap<(i64, String), Vec<&'a Tree<Task>>> = BTreeMap::new();
+        for task in tasks {
+            for key in self.keys(task, state) {
+                groups.entry(key).or_default().push(task);
+            }
+        }
+        groups
+            .into_iter()
+            .map(|((_, title), tasks)| Group { title, tasks })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{
+        api::rest::{Label, Priority},
+        config::Config,
+    };
+
+    #[test]
+    fn groups_tasks() {
+        let cfg = Config::default();
+        let tasks = vec![
+            Tree::new(Task {
+                labels: vec!["b".to_string(), "a".to_string()],
+                responsible_uid: Some("2222".to_string()),
+                ..Task::new("1", "both")
+            }),
+            Tree::new(Task {
+                priority: Priority::Urgent,
+                ..Task::new("2", "none")
+            }),
+            Tree::new(Task {
+                labels: vec!["b".to_string()],
+                responsible_uid: Some("1111".to_string()),
+                ..Task::new("3", "one")
+            }),
+        ];
+        let state = State {
+            tasks: Vec::new(),
+            projects: HashMap::new(),
+            sections: HashMap::new(),
+            labels: HashMap::from([
+                ("a".to_string(), Label::new("1", "a")),
+                (
+                    "b".to_string(),
+                    Label {
+                        order: 1,
+                        ..Label::new("2", "b")
+                    },
+                ),
+            ]),
+            config: &cfg,
+            template: None,
+            default_template: Default::default(),
+        };
+        let titles = |by: GroupBy| {
+            by.groups(&tasks, &state)
+                .into_iter()
+                .map(|g| {
+                    (
+                        g.title,
+                        g.tasks.iter().map(|t| t.id.as_str()).collect::<Vec<_>>(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            titles(GroupBy::Label),
+            vec![
+                ("@a".to_string(), vec!["1"]),
+                ("@b".to_string(), vec!["1", "3"]),
+                ("(No label)".to_string(), vec!["2"]),
+            ]
+        );
+        assert_eq!(
+            titles(GroupBy::Priority),
+            vec![
+                ("p1".to_string(), vec!["2"]),
+                ("p4".to_string(), vec!["1", "3"]),
+            ]
+        );
+        assert_eq!(
+            titles(GroupBy::Assignee),
+            vec![
+                ("Assigned to user ID 1111".to_string(), vec!["3"]),
+                ("Assigned to user ID 2222".to_string(), vec!["1"]),
+                ("(Unassigned)".to_string(), vec!["2"]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Parses tokens that are written inline with the name of a task, like
+//! `Write report #Work/Q3 @deep p1 tomorrow 9am`.
+//!
+//! * `#name` sets the project, and `#name/section` also sets the section in that project.
+//! * `@name` adds a label, and can be used multiple times.
+//! * `p1` to `p4` set the priority.
+//! * `due:` marks the start of the due string, for when no other token comes before it.
+//!
+//! The name of the task is everything before the first token. All other words after it are used
+//! as the due string.
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+
+use crate::{
+    api::rest::{CreateTask, Label, Project, Section, TaskDue},
+    interactive::{self, Ambiguity},
+    tasks::Priority,
+};
+
+/// The name of a task and the tokens that were found in it.
+#[derive(Debug, Default, PartialEq)]
+pub struct Inline {
+    pub content: String,
+    pub project: Option<String>,
+    pub section: Option<String>,
+    pub labels: Vec<String>,
+    pub priority: Option<Priority>,
+    pub due: Option<String>,
+}
+
+/// Values that are used for tasks which don't set them inline.
+pub struct Defaults<'a> {
+    pub project: Option<&'a Project>,
+    pub section: Option<&'a Section>,
+    pub labels: &'a [Label],
+    pub priority: Option<Priority>,
+    pub due: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Inline {
+    pub fn parse(text: &str) -> Inline {
+        let mut inline = Inline::default();
+        let mut content = Vec::new();
+        let mut due = Vec::new();
+        let mut tokens = false;
+        for word in text.split_whitespace() {
+            if let Some(project) = word.strip_prefix('#').filter(|p| !p.is_empty()) {
+                match project.split_once('/') {
+                    Some((project, section)) => {
+                        inline.project = Some(project.to_string());
+                        inline.section = Some(section.to_string()).filter(|s| !s.is_empty());
+                    }
+                    None => inline.project = Some(project.to_string()),
+                }
+            } else if let Some(label) = word.strip_prefix('@').filter(|l| !l.is_empty()) {
+                inline.labels.push(label.to_string());
+            } else if let Some(priority) = priority(word) {
+                inline.priority = Some(priority);
+            } else if let Some(rest) = word.strip_prefix("due:") {
+                if !rest.is_empty() {
+                    due.push(rest);
+                }
+            } else if tokens {
+                due.push(word);
+                continue;
+            } else {
+                content.push(word);
+                continue;
+            }
+            tokens = true;
+        }
+        inline.content = content.join(" ");
+        if !due.is_empty() {
+            inline.due = Some(due.join(" "));
+        }
+        inline
+    }
+
+    /// Creates the task by resolving the names of the tokens, falling back to the defaults where
+    /// nothing is set. Subtasks don't get the default project and section, as they are created
+    /// in the project of their parent.
+    pub fn resolve(
+        self,
+        subtask: bool,
+        defaults: &Defaults<'_>,
+        projects: &[Project],
+        sections: &[Section],
+        labels: &[Label],
+        ambiguity: Ambiguity,
+    ) -> Result<CreateTask> {
+        let mut create = CreateTask {
+            content: self.content,
+            description: defaults.description.clone(),
+            priority: self.priority.or(defaults.priority).map(|p| p.into()),
+            due: self
+                .due
+                .or_else(|| defaults.due.clone())
+                .map(TaskDue::String),
+            labels: defaults.labels.iter().map(|l| l.name.clone()).collect(),
+            ..Default::default()
+        };
+        if create.content.is_empty() {
+            return Err(eyre!("task name can't be empty"));
+        }
+        for label in &self.labels {
+            let label = interactive::fuzz_select(labels, label, ambiguity)?
+                .name
+                .clone();
+            if !create.labels.contains(&label) {
+                create.labels.push(label);
+            }
+        }
+        match self.project {
+            Some(project) => {
+                let project = interactive::fuzz_select(projects, &project, ambiguity)?;
+                create.project_id = Some(project.id.clone());
+                if let Some(section) = self.section {
+                    let sections = sections
+                        .iter()
+                        .filter(|s| s.project_id == project.id)
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    create.section_id = Some(
+                        interactive::fuzz_select(&sections, &section, ambiguity)
+                            .wrap_err_with(|| {
+                                format!("no section matching '{}' in {}", section, project.name)
+                            })?
+                            .id
+                            .clone(),
+                    );
+                }
+            }
+            None if !subtask => {
+                create.project_id = defaults.project.map(|p| p.id.clone());
+                create.section_id = defaults.section.map(|s| s.id.clone());
+            }
+            None => {}
+        }
+        Ok(create)
+    }
+}
+
+fn priority(word: &str) -> Option<Priority> {
+    word.strip_prefix('p')
+        .and_then(|p| p.parse::<usize>().ok())
+        .and_then(|p| Priority::try_from(p).ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_tokens() {
+        let inline = Inline::parse("Write report #Work/Q3 @deep @quick p1 tomorrow 9am");
+        assert_eq!(
+            inline,
+            Inline {
+                content: "Write report".to_string(),
+                project: Some("Work".to_string()),
+                section: Some("Q3".to_string()),
+                labels: vec!["deep".to_string(), "quick".to_string()],
+                priority: Some(Priority::Urgent),
+                due: Some("tomorrow 9am".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_due_marker() {
+        let inline = Inline::parse("Call mom due: next monday");
+        assert_eq!(inline.content, "Call mom");
+        assert_eq!(inline.due.as_deref(), Some("next monday"));
+        let inline = Inline::parse("Call due:friday 3pm #Work");
+        assert_eq!(inline.content, "Call");
+        assert_eq!(inline.project.as_deref(), Some("Work"));
+        assert_eq!(inline.due.as_deref(), Some("friday 3pm"));
+    }
+
+    #[test]
+    fn keeps_plain_words() {
+        assert_eq!(
+            Inline::parse("Fix # in p5 report"),
+            Inline {
+                content: "Fix # in p5 report".to_string(),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn resolves_names() {
+        let projects = vec![Project::new("1", "Work"), Project::new("2", "Personal")];
+        let sections = vec![
+            Section::new("3", "2", "Weekend"),
+            Section::new("4", "1", "Q3 Planning"),
+        ];
+        let labels = vec![Label::new("1", "writing"), Label::new("2", "errand")];
+        let defaults = Defaults {
+            project: Some(&projects[1]),
+            section: Some(&sections[0]),
+            labels: &labels[1..],
+            priority: Some(Priority::High),
+            due: Some("today".to_string()),
+            description: None,
+        };
+        let create = Inline::parse("Draft #wrk/q3 @writ p1")
+            .resolve(
+                false,
+                &defaults,
+                &projects,
+                &sections,
+                &labels,
+                Ambiguity::Fail,
+            )
+            .unwrap();
+        assert_eq!(create.project_id.as_deref(), Some("1"));
+        assert_eq!(create.section_id.as_deref(), Some("4"));
+        assert_eq!(create.labels, vec!["errand", "writing"]);
+        assert_eq!(create.priority, Some(crate::api::rest::Priority::Urgent));
+        assert_eq!(create.due, Some(TaskDue::String("today".to_string())));
+
+        let create = Inline::parse("Plain")
+            .resolve(
+                false,
+                &defaults,
+                &projects,
+                &sections,
+                &labels,
+                Ambiguity::Fail,
+            )
+            .unwrap();
+        assert_eq!(create.project_id.as_deref(), Some("2"));
+        assert_eq!(create.section_id.as_deref(), Some("3"));
+
+        let create = Inline::parse("Child")
+            .resolve(
+                true,
+                &defaults,
+                &projects,
+                &sections,
+                &labels,
+                Ambiguity::Fail,
+            )
+            .unwrap();
+        assert_eq!(create.project_id, None);
+        assert_eq!(create.section_id, None);
+
+        assert!(
+            Inline::parse("Draft #personal/q3")
+                .resolve(
+                    false,
+                    &defaults,
+                    &projects,
+                    &sections,
+                    &labels,
+                    Ambiguity::Fail,
+                )
+                .is_err()
+        );
+    }
+}
@@ -2,19 +2,30 @@ use std::ops::Not;
 
 use crate::{
     api::{
-        rest::{Gateway, Project, Section, Task},
-        tree::Tree,
+        rest::{Gateway, Project, ResolvedTask, Section, Task},
+        tree::{Tree, TreeFlattenExt},
     },
     config::Config,
-    interactive, labels,
+    filters, interactive, labels, query,
     tasks::{
-        close, edit, filter,
-        state::{State, TaskMenu},
+        agenda, close,
+        columns::Column,
+        edit, filter,
+        group::GroupBy,
+        refs::Refs,
+        sort::{self, SortBy},
+        state::State,
+        template::Template,
     },
 };
-use color_eyre::{Result, eyre::WrapErr};
-use owo_colors::OwoColorize;
-use strum::{Display, FromRepr, VariantNames};
+use chrono::Utc;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use owo_colors::{OwoColorize, Stream};
+use serde::Serialize;
+use strum::{Display, EnumIter, FromRepr, IntoEnumIterator, VariantNames};
 
 use super::create;
 
@@ -22,6 +33,9 @@ use super::create;
 pub struct Params {
     #[clap(flatten)]
     filter: filter::Filter,
+    /// Uses the query of the saved filter with the closest name instead of `--filter`.
+    #[arg(long = "saved", value_name = "NAME", conflicts_with = "filter")]
+    saved: Option<String>,
     /// Disables interactive mode and simply displays the list.
     #[arg(short = 'n', long = "nointeractive")]
     nointeractive: bool,
@@ -35,15 +49,52 @@ pub struct Params {
     /// match the filter.
     #[arg(short = 'e', long = "expand")]
     expand: bool,
+    /// Evaluates the filter locally against all tasks instead of sending it to the Todoist API.
+    /// Supports `&`, `|`, `!`, parentheses and terms like `today`, `overdue`, `p1`, `#project`,
+    /// `##project`, `@label`, `/section`, `due before: <date>`, `no date` and `search: <text>`.
+    #[arg(long = "local-filter")]
+    local_filter: bool,
     /// Enables a continuous super-interactive mode, so that after each operation more operations
     /// can be done until the program is exited from.
     #[arg(short = 'i', long = "interactive")]
     continuous: bool,
+    /// Selects the columns and their order when using CSV or TSV output.
+    #[arg(
+        value_enum,
+        long = "columns",
+        value_delimiter = ',',
+        default_value = "id,content,project,section,labels,priority,due,url"
+    )]
+    columns: Vec<Column>,
+    /// Displays task rows with a template like `'{id} {priority} {content} [{project}]'`, or
+    /// the name of a format from the config. Fields take directives such as `{content:30.30}`
+    /// for width and truncation or `{due:red}` for colors.
+    #[arg(long = "format")]
+    format: Option<String>,
+    /// Groups tasks under headings with the number of tasks in each group. Subtasks stay with
+    /// their parent task.
+    #[arg(value_enum, short = 'g', long = "group-by")]
+    group_by: Option<GroupBy>,
+    /// Sorts tasks by this field, with subtasks sorted under their parent task.
+    #[arg(value_enum, short = 's', long = "sort")]
+    sort: Option<SortBy>,
+    /// Reverses the order of the tasks.
+    #[arg(short = 'r', long = "reverse")]
+    reverse: bool,
 }
 
 /// List lists the tasks of the current user accessing the gateway with the given filter.
-pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    if params.continuous && !params.nointeractive {
+pub async fn list(mut params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    if let Some(name) = params.saved.take() {
+        let filters = gw.filters().await?;
+        let saved = filters::select(
+            &filters,
+            Some(&name),
+            interactive::Ambiguity::new(!params.nointeractive && cfg.output.is_text()),
+        )?;
+        params.filter.set_filter(Some(&saved.query));
+    }
+    if params.continuous && !params.nointeractive && cfg.output.is_text() {
         return list_interactive(params, gw, cfg).await;
     }
     match list_action(&params, gw, cfg).await {
@@ -53,18 +104,20 @@ pub async fn list(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
 }
 
 async fn list_action(params: &Params, gw: &Gateway, cfg: &Config) -> Result<()> {
-    let state = if params.expand {
-        State::fetch_full_tree(Some(&params.filter.select(cfg)), gw, cfg).await
-    } else {
-        State::fetch_tree(Some(&params.filter.select(cfg)), gw, cfg).await
-    }?;
-    let state = filter_list(state, params).await?;
-    if params.nointeractive {
-        list_tasks(&state.tasks, &state);
+    let lists = fetch(params, &params.filter.select(cfg)?, gw, cfg).await?;
+    if params.nointeractive || !cfg.output.is_text() {
+        let mut rows = Rows::new(&lists, cfg);
+        list_tasks(&lists, params, &mut rows)?;
+        // The tasks were already listed, so they can still be referenced by their IDs.
+        if cfg.output.is_text()
+            && let Err(e) = rows.refs.save(cfg)
+        {
+            eprintln!("warning: unable to save the numbers of the listed tasks: {e:#}");
+        }
     } else {
-        match state.select_task()? {
-            Some(task) => select_task_option(task, &state, gw).await?,
-            None => {
+        match select(&lists, false)? {
+            Some(Entry::Task(task, state)) => select_task_option(task, state, gw).await?,
+            _ => {
                 println!("No selection was made");
             }
         }
@@ -94,16 +147,11 @@ async fn list_interactive_action(
     gw: &Gateway,
     cfg: &Config,
 ) -> Result<ListAction> {
-    let filter = params.filter.select(cfg);
-    let state = if params.expand {
-        State::fetch_full_tree(Some(&filter), gw, cfg).await
-    } else {
-        State::fetch_tree(Some(&filter), gw, cfg).await
-    }?;
+    let filter = params.filter.select(cfg)?;
+    let lists = fetch(params, &filter, gw, cfg).await?;
 
-    let state = filter_list(state, params).await?;
-    match state.select_or_menu()? {
-        TaskMenu::Menu => {
+    match select(&lists, true)? {
+        Some(Entry::Action) => {
             match interactive::select(
                 "Select Action:",
                 &[
@@ -124,34 +172,155 @@ async fn list_interactive_action(
             )? {
                 // TODO change this once we have async closures and can iterate over a Vec<(str, async Fn)>
                 Some(0) => create::create(create::Params {}, gw, cfg).await?,
-                Some(1) => {
-                    let filter = filter.is_empty().not().then_some(filter);
-                    params.filter.set_filter(Some(
-                        &interactive::input_optional("Filter", filter)?.unwrap_or_default(),
-                    ));
-                }
+                Some(1) => set_filter(params, filter, gw).await?,
                 Some(2) => params.filter.set_filter(Some("all")),
                 Some(3) => params.filter.set_filter(Some("#inbox")),
-                Some(4) => params.filter.set_filter(Some(&cfg.default_filter)),
+                Some(4) => {
+                    agenda::agenda(
+                        agenda::Params {
+                            days: agenda::DEFAULT_DAYS,
+                        },
+                        gw,
+                        cfg,
+                    )
+                    .await?
+                }
                 Some(5) => params.filter.set_filter(Some("(today | overdue)")),
                 Some(_) => unreachable!(),
                 None => {}
             };
             Ok(ListAction::Action)
         }
-        TaskMenu::Select(task) => {
-            select_task_option(task, &state, gw).await?;
+        Some(Entry::Task(task, state)) => {
+            select_task_option(task, state, gw).await?;
             Ok(ListAction::Action)
         }
-        TaskMenu::None => {
+        Some(Entry::Heading) | None => {
             println!("No selection was made");
             Ok(ListAction::Cancel)
         }
     }
 }
 
+/// The tasks of one of the comma separated queries of a filter.
+struct TaskList<'a> {
+    /// Shown as a heading if the filter has more than one query.
+    query: String,
+    state: State<'a>,
+}
+
+/// An entry that can be selected from the task lists.
+enum Entry<'s, 'a> {
+    Action,
+    Heading,
+    Task(&'s Tree<Task>, &'s State<'a>),
+}
+
+/// Lets the user select a task, with the tasks of each query under their own heading if there
+/// is more than one query. Adds an entry for the action menu in front if `action` is set.
+fn select<'s, 'a>(lists: &'s [TaskList<'a>], action: bool) -> Result<Option<Entry<'s, 'a>>> {
+    let mut entries = Vec::new();
+    if action {
+        entries.push((
+            format!("{} {}", ">".green(), "Action...".bold()),
+            Entry::Action,
+        ));
+    } else if lists.iter().all(|l| l.state.tasks.is_empty()) {
+        return Err(eyre!("no tasks were found using the current filter"));
+    }
+    for list in lists {
+        if lists.len() > 1 {
+            entries.push((heading(list), Entry::Heading));
+        }
+        for task in list.state.tasks.flat_tree() {
+            entries.push((list.state.row(task), Entry::Task(task, &list.state)));
+        }
+    }
+    loop {
+        let result = interactive::select(
+            "Select task",
+            &entries.iter().map(|(text, _)| text).collect::<Vec<_>>(),
+        )?;
+        // Headings only structure the list, so the selection is repeated for them.
+        match result {
+            Some(index) if matches!(entries[index].1, Entry::Heading) => continue,
+            Some(index) => return Ok(Some(entries.swap_remove(index).1)),
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Returns the heading of a task list with the number of tasks in it.
+fn heading(list: &TaskList) -> String {
+    format!(
+        "{} ({})",
+        list.query
+            .if_supports_color(Stream::Stdout, |text| text.bold()),
+        list.state.tasks.flat_tree().len()
+    )
+}
+
+/// Lets the user pick one of the saved filters or type in a new filter.
+async fn set_filter(params: &mut Params, filter: String, gw: &Gateway) -> Result<()> {
+    let saved = gw.filters().await?;
+    if !saved.is_empty() {
+        let choice = interactive::select(
+            "Select Filter",
+            &["Custom Filter...".to_string()]
+                .into_iter()
+                .chain(saved.iter().map(ToString::to_string))
+                .collect::<Vec<_>>(),
+        )?;
+        match choice {
+            Some(0) => {}
+            Some(index) => {
+                params.filter.set_filter(Some(&saved[index - 1].query));
+                return Ok(());
+            }
+            None => return Ok(()),
+        }
+    }
+    let filter = filter.is_empty().not().then_some(filter);
+    params.filter.set_filter(Some(
+        &interactive::input_optional("Filter", filter)?.unwrap_or_default(),
+    ));
+    Ok(())
+}
+
+/// Fetches the tasks matching each comma separated query of the filter, either through the API
+/// or by evaluating the queries locally, and filters them down based on the params.
+async fn fetch<'a>(
+    params: &Params,
+    filter: &str,
+    gw: &Gateway,
+    cfg: &'a Config,
+) -> Result<Vec<TaskList<'a>>> {
+    let queries = query::split(filter);
+    let states = if params.local_filter {
+        let queries = queries
+            .iter()
+            .map(|q| filter::parse(q))
+            .collect::<Result<Vec<_>>>()?;
+        State::fetch_local(&queries, params.expand, gw, cfg).await
+    } else {
+        State::fetch_queries(&queries, params.expand, gw, cfg).await
+    }?;
+    let mut lists = Vec::new();
+    for (query, state) in queries.into_iter().zip(states) {
+        lists.push(TaskList {
+            query: query.to_string(),
+            state: filter_list(state, params).await?,
+        });
+    }
+    Ok(lists)
+}
+
 /// Show a list that's filtered down based on the params.
 async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'a>> {
+    let mut state = state;
+    if params.format.is_some() {
+        state.template = Template::resolve(params.format.as_deref(), state.config)?;
+    }
     let projects = state
         .projects
         .values()
@@ -167,12 +336,13 @@ async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'
         .values()
         .map(ToOwned::to_owned)
         .collect::<Vec<_>>();
-    let project = params.project.optional(&projects)?;
-    let section = params.section.optional(&sections)?;
+    let ambiguity =
+        interactive::Ambiguity::new(!params.nointeractive && state.config.output.is_text());
+    let project = params.project.optional(&projects, ambiguity)?;
+    let section = params.section.optional(&sections, ambiguity)?;
     let labels = params
         .label
-        .labels(&labels, labels::Selection::AllowEmpty)?;
-    let mut state = state;
+        .labels(&labels, labels::Selection::AllowEmpty, ambiguity)?;
     if let Some(p) = project {
         state = state.filter(|tree| tree.project_id == *p.id);
     }
@@ -187,19 +357,180 @@ async fn filter_list<'a>(state: State<'a>, params: &'_ Params) -> Result<State<'
                 .any(|l| tree.labels.contains(&l))
         });
     }
+    sort::sort(
+        &mut state.tasks,
+        params.sort,
+        params.reverse,
+        &state.projects,
+    );
     Ok(state)
 }
 
-fn list_tasks<'a>(tasks: &'a [Tree<Task>], state: &'a State) {
-    let mut tasks = tasks.to_vec();
-    tasks.sort();
-    for task in tasks.iter() {
-        println!("{}", state.table_task(task));
-        list_tasks(&task.subitems, state);
+/// Prints task rows with a number in front of them, which can be used to reference the task in
+/// other commands.
+struct Rows {
+    refs: Refs,
+    width: usize,
+}
+
+impl Rows {
+    fn new(lists: &[TaskList], cfg: &Config) -> Self {
+        let count: usize = lists.iter().map(|l| l.state.tasks.flat_tree().len()).sum();
+        Self {
+            refs: Refs::new(cfg.override_time.unwrap_or_else(Utc::now)),
+            width: count.to_string().len(),
+        }
+    }
+
+    fn print(&mut self, task: &Tree<Task>, state: &State) {
+        let number = self.refs.add(task.id.clone());
+        println!(
+            "{} {}",
+            format!("{number:>width$}", width = self.width)
+                .if_supports_color(Stream::Stdout, |text| text.dimmed()),
+            state.row(task)
+        );
     }
 }
 
-#[derive(Display, FromRepr, VariantNames)]
+fn list_tasks(lists: &[TaskList], params: &Params, rows: &mut Rows) -> Result<()> {
+    let [list] = lists else {
+        return list_queries(lists, params, rows);
+    };
+    let state = &list.state;
+    let tasks = state.tasks.flat_tree();
+    if state.config.output.is_tabular() {
+        return table(lists, params);
+    }
+    if let Some(group_by) = params.group_by {
+        return list_groups(group_by, state, rows);
+    }
+    if !state.config.output.is_text() {
+        return state.config.output.list(
+            &tasks
+                .into_iter()
+                .map(|task| state.resolved_task(task))
+                .collect::<Vec<_>>(),
+        );
+    }
+    for task in tasks {
+        rows.print(task, state);
+    }
+    Ok(())
+}
+
+/// Lists the tasks of each query under its own heading.
+fn list_queries(lists: &[TaskList], params: &Params, rows: &mut Rows) -> Result<()> {
+    let Some(output) = lists.first().map(|l| &l.state.config.output) else {
+        return Ok(());
+    };
+    if output.is_tabular() {
+        return table(lists, params);
+    }
+    if !output.is_text() {
+        return output.list(
+            &lists
+                .iter()
+                .map(|list| TaskGroup {
+                    group: &list.query,
+                    tasks: list
+                        .state
+                        .tasks
+                        .flat_tree()
+                        .into_iter()
+                        .map(|task| list.state.resolved_task(task))
+                        .collect(),
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    for (index, list) in lists.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!("{}", heading(list));
+        match params.group_by {
+            Some(group_by) => list_groups(group_by, &list.state, rows)?,
+            None => {
+                for task in list.state.tasks.flat_tree() {
+                    rows.print(task, &list.state);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Shows the tasks of all lists as a single table.
+fn table(lists: &[TaskList], params: &Params) -> Result<()> {
+    let Some(output) = lists.first().map(|l| &l.state.config.output) else {
+        return Ok(());
+    };
+    output.table(
+        &params
+            .columns
+            .iter()
+            .map(Column::to_string)
+            .collect::<Vec<_>>(),
+        &lists
+            .iter()
+            .flat_map(|list| {
+                list.state.tasks.flat_tree().into_iter().map(|task| {
+                    params
+                        .columns
+                        .iter()
+                        .map(|c| c.value(task, &list.state))
+                        .collect()
+                })
+            })
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Used to serialize a group of tasks when listing tasks grouped by a field.
+#[derive(Serialize)]
+struct TaskGroup<'a> {
+    group: &'a str,
+    tasks: Vec<ResolvedTask<'a>>,
+}
+
+fn list_groups(group_by: GroupBy, state: &State, rows: &mut Rows) -> Result<()> {
+    let groups = group_by.groups(&state.tasks, state);
+    if !state.config.output.is_text() {
+        return state.config.output.list(
+            &groups
+                .iter()
+                .map(|group| TaskGroup {
+                    group: &group.title,
+                    tasks: group
+                        .tasks
+                        .iter()
+                        .flat_map(|task| task.flatten())
+                        .map(|task| state.resolved_task(task))
+                        .collect(),
+                })
+                .collect::<Vec<_>>(),
+        );
+    }
+    for (index, group) in groups.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        println!(
+            "{} ({})",
+            group
+                .title
+                .if_supports_color(Stream::Stdout, |text| text.bold()),
+            group.count()
+        );
+        for task in group.tasks.iter().flat_map(|task| task.flatten()) {
+            rows.print(task, state);
+        }
+    }
+    Ok(())
+}
+
+#[derive(Display, EnumIter, Clone, Copy)]
 enum TaskOptions {
     #[strum(serialize = "Close Task")]
     Close,
@@ -209,14 +540,18 @@ enum TaskOptions {
     Quit,
 }
 
-async fn select_task_option<'a>(
+pub(super) async fn select_task_option<'a>(
     task: &'a Tree<Task>,
     state: &'a State<'_>,
     gw: &'_ Gateway,
 ) -> Result<()> {
     println!("{}", state.full_task(task));
-    let result = match make_selection(TaskOptions::VARIANTS)? {
-        Some(index) => TaskOptions::from_repr(index).unwrap(),
+    // Completed tasks, like the ones found by searching, can't be closed again.
+    let options = TaskOptions::iter()
+        .filter(|o| !task.checked || !matches!(o, TaskOptions::Close | TaskOptions::Complete))
+        .collect::<Vec<_>>();
+    let result = match make_selection(&options)? {
+        Some(index) => options[index],
         None => {
             println!("No selection made");
             return Ok(());
@@ -224,26 +559,10 @@ async fn select_task_option<'a>(
     };
     match result {
         TaskOptions::Close => {
-            close::close(
-                close::Params {
-                    task: task.id.clone().into(),
-                    complete: false,
-                },
-                gw,
-                state.config,
-            )
-            .await?
+            close::close(close::Params::new(task.id.clone(), false), gw, state.config).await?
         }
         TaskOptions::Complete => {
-            close::close(
-                close::Params {
-                    task: task.id.clone().into(),
-                    complete: true,
-                },
-                gw,
-                state.config,
-            )
-            .await?
+            close::close(close::Params::new(task.id.clone(), true), gw, state.config).await?
         }
         TaskOptions::Edit => edit_task(task, gw, state.config).await?,
         TaskOptions::Quit => {}
@@ -1,13 +1,23 @@
 //! Controls things that work with [`crate::api::rest::Task`]s.
 pub mod add;
+pub mod agenda;
+mod batch;
+mod bulk;
 pub mod close;
+mod columns;
 pub mod comment;
 pub mod create;
 pub mod edit;
-mod filter;
+pub mod filter;
+mod group;
+pub mod inline;
 pub mod list;
 mod priority;
+mod refs;
+pub mod search;
+mod sort;
 mod state;
+mod template;
 pub mod view;
 
 pub use priority::*;
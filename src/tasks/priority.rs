@@ -3,7 +3,7 @@ use color_eyre::eyre::eyre;
 use serde::{Deserialize, Serialize};
 
 /// Maps priority from arguments to API priorities.
-#[derive(clap::ValueEnum, Debug, Copy, Clone, Deserialize, Serialize)]
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Priority {
     #[value(name = "1")]
     Urgent,
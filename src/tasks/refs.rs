@@ -0,0 +1,117 @@
+//! Short numeric references to the tasks of the last listing, so `close 3` can be used instead of
+//! the full task ID.
+use std::fs;
+
+use chrono::{DateTime, Duration, Local, Utc};
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{api::rest::TaskID, config::Config};
+
+/// The file in the state directory that keeps the references.
+const REFS_FILE: &str = "refs.json";
+
+/// How long references can be used before the tasks have to be listed again, as the tasks they
+/// point to might have changed in the meantime.
+const STALE_AFTER: Duration = Duration::days(1);
+
+/// Maps the numbers shown in front of listed tasks to their IDs.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Refs {
+    /// When the tasks were listed.
+    listed_at: DateTime<Utc>,
+    /// The IDs of the listed tasks, where the task with number 1 comes first.
+    tasks: Vec<TaskID>,
+}
+
+impl Refs {
+    pub fn new(listed_at: DateTime<Utc>) -> Self {
+        Self {
+            listed_at,
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Adds a task to the references and returns the number it can be referenced with.
+    pub fn add(&mut self, id: TaskID) -> usize {
+        self.tasks.push(id);
+        self.tasks.len()
+    }
+
+    /// Loads the references of the last listing, if there is one.
+    pub fn load(cfg: &Config) -> Result<Option<Self>> {
+        let file = cfg.state_file(REFS_FILE)?;
+        let data = match fs::read_to_string(&file) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).wrap_err_with(|| format!("unable to read {}", file.display()));
+            }
+        };
+        let refs = serde_json::from_str(&data)
+            .wrap_err_with(|| format!("unable to parse {}", file.display()))?;
+        Ok(Some(refs))
+    }
+
+    /// Replaces the stored references with these.
+    pub fn save(&self, cfg: &Config) -> Result<()> {
+        let file = cfg.state_file(REFS_FILE)?;
+        if let Some(dir) = file.parent() {
+            fs::create_dir_all(dir)
+                .wrap_err_with(|| format!("unable to create {}", dir.display()))?;
+        }
+        fs::write(&file, serde_json::to_string(self)?)
+            .wrap_err_with(|| format!("unable to write {}", file.display()))
+    }
+
+    /// Returns the ID of the task with the number, or `None` if the input is not the number of a
+    /// listed task. Fails if the listing is too old to be trusted.
+    pub fn resolve(&self, input: &str, now: DateTime<Utc>) -> Result<Option<&TaskID>> {
+        let Some(id) = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .and_then(|index| self.tasks.get(index))
+        else {
+            return Ok(None);
+        };
+        if now - self.listed_at > STALE_AFTER {
+            return Err(eyre!(
+                "task number {input} is from a listing at {}, which might be outdated. Use `doist list -n` to number the tasks again",
+                self.listed_at
+                    .with_timezone(&Local)
+                    .format("%Y-%m-%d %H:%M")
+            ));
+        }
+        Ok(Some(id))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn resolves_numbers() {
+        let now = Utc::now();
+        let mut refs = Refs::new(now);
+        assert_eq!(refs.add("7000001".to_string()), 1);
+        assert_eq!(refs.add("7000002".to_string()), 2);
+
+        assert_eq!(
+            refs.resolve("2", now).unwrap(),
+            Some(&"7000002".to_string())
+        );
+        assert_eq!(refs.resolve("0", now).unwrap(), None);
+        assert_eq!(refs.resolve("3", now).unwrap(), None);
+        assert_eq!(refs.resolve("7000001", now).unwrap(), None);
+        assert_eq!(refs.resolve("buy milk", now).unwrap(), None);
+
+        let later = now + Duration::days(2);
+        assert!(refs.resolve("1", later).is_err());
+        assert_eq!(refs.resolve("3", later).unwrap(), None);
+    }
+}
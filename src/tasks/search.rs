@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, Utc};
+use color_eyre::Result;
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use owo_colors::{OwoColorize, Stream, Style};
+use strum::Display;
+
+use crate::{
+    api::{
+        rest::{Comment, Gateway, Task, TaskID},
+        tree::{Tree, TreeFlattenExt},
+    },
+    config::Config,
+    interactive,
+};
+
+use super::{list, state::State};
+
+/// How far back completed tasks are searched. The Todoist API allows at most 3 months.
+const COMPLETED_WEEKS: i64 = 12;
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// The text to search for. Matches fuzzily, so `wrt rpt` finds "Write report".
+    text: String,
+    /// Also searches the comments of tasks.
+    #[arg(short = 'c', long = "comments")]
+    comments: bool,
+    /// Also searches tasks that were completed in the last 12 weeks.
+    #[arg(long = "completed")]
+    completed: bool,
+    /// Only lists the results instead of selecting a task.
+    #[arg(short = 'n', long = "nointeractive")]
+    nointeractive: bool,
+}
+
+/// The part of a task that matched the search text, in the order they are preferred in.
+#[derive(Debug, Display, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "lowercase")]
+enum Field {
+    Content,
+    Description,
+    Comment,
+}
+
+/// A task that matched the search text, with the line of the field that matched best.
+struct Match<'a> {
+    task: &'a Tree<Task>,
+    score: i64,
+    field: Field,
+    text: &'a str,
+    /// The indices of the matched characters in `text`.
+    indices: Vec<usize>,
+}
+
+/// Searches tasks by their content and description, and optionally their comments, showing the
+/// best matches first.
+pub async fn search(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let now = cfg.override_time.unwrap_or_else(Utc::now);
+    let (state, completed, comments) = tokio::try_join!(
+        State::fetch_tree(Some("all"), gw, cfg),
+        async {
+            if params.completed {
+                gw.completed_tasks(now - Duration::weeks(COMPLETED_WEEKS), now)
+                    .await
+            } else {
+                Ok(Vec::new())
+            }
+        },
+        async {
+            if params.comments {
+                gw.comments().await
+            } else {
+                Ok(Vec::new())
+            }
+        },
+    )?;
+    // Results are ranked instead of being shown as a tree.
+    let tasks = state
+        .tasks
+        .flat_tree()
+        .into_iter()
+        .map(|t| t.item.clone())
+        .chain(completed)
+        .map(Tree::new)
+        .collect::<Vec<_>>();
+    let mut task_comments: HashMap<&TaskID, Vec<&Comment>> = HashMap::new();
+    for comment in &comments {
+        if let Some(id) = &comment.item_id {
+            task_comments.entry(id).or_default().push(comment);
+        }
+    }
+    let matches = rank(&tasks, &task_comments, &params.text);
+
+    if !cfg.output.is_text() {
+        return cfg.output.list(
+            &matches
+                .iter()
+                .map(|m| state.resolved_task(m.task))
+                .collect::<Vec<_>>(),
+        );
+    }
+    if matches.is_empty() {
+        println!("No tasks found matching `{}`", params.text);
+        return Ok(());
+    }
+    let rows = matches.iter().map(|m| row(m, &state)).collect::<Vec<_>>();
+    if params.nointeractive {
+        for row in rows {
+            println!("{row}");
+        }
+        return Ok(());
+    }
+    match interactive::select("Select task", &rows)? {
+        Some(index) => list::select_task_option(matches[index].task, &state, gw).await,
+        None => {
+            println!("No selection was made");
+            Ok(())
+        }
+    }
+}
+
+/// Returns the tasks that match the text, best matches first.
+fn rank<'a>(
+    tasks: &'a [Tree<Task>],
+    comments: &HashMap<&TaskID, Vec<&'a Comment>>,
+    text: &str,
+) -> Vec<Match<'a>> {
+    let matcher = SkimMatcherV2::default();
+    let mut matches = tasks
+        .iter()
+        .filter_map(|task| {
+            let fields = [(Field::Content, task.content.as_str())]
+                .into_iter()
+                .chain(lines(&task.description).map(|l| (Field::Description, l)))
+                .chain(
+                    comments
+                        .get(&task.id)
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|c| lines(&c.content).map(|l| (Field::Comment, l))),
+                );
+            let mut best: Option<Match> = None;
+            for (field, line) in fields {
+                let Some((score, indices)) = matcher.fuzzy_indices(line, text) else {
+                    continue;
+                };
+                // Earlier fields win on ties, so content matches are preferred.
+                if best.as_ref().is_none_or(|b| score > b.score) {
+                    best = Some(Match {
+                        task,
+                        score,
+                        field,
+                        text: line,
+                        indices,
+                    });
+                }
+            }
+            best
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.field.cmp(&b.field))
+            .then_with(|| a.task.cmp(b.task))
+    });
+    matches
+}
+
+/// Returns the non-empty lines of the text without surrounding whitespace.
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|l| !l.is_empty())
+}
+
+/// Shows the task as a row in a list. Matches in the content are highlighted in the row, while
+/// matches in other fields are shown after it. Completed tasks are marked as such.
+fn row(m: &Match, state: &State) -> String {
+    let mut row = if m.field == Field::Content {
+        let task = Tree::new(Task {
+            content: highlight(m.text, &m.indices),
+            ..m.task.item.clone()
+        });
+        state.table_task(&task).to_string()
+    } else {
+        state.table_task(m.task).to_string()
+    };
+    if m.task.checked {
+        row = format!(
+            "{row} {}",
+            "(completed)".if_supports_color(Stream::Stdout, |text| text.dimmed())
+        );
+    }
+    if m.field != Field::Content {
+        row = format!(
+            "{row} {} {}",
+            format!("{}:", m.field).if_supports_color(Stream::Stdout, |text| text.dimmed()),
+            highlight(m.text, &m.indices)
+        );
+    }
+    row
+}
+
+/// Highlights the characters at the given indices in the text.
+fn highlight(text: &str, indices: &[usize]) -> String {
+    highlight_with(text, indices, |c| {
+        c.if_supports_color(Stream::Stdout, |c| c.style(Style::new().yellow().bold()))
+            .to_string()
+    })
+}
+
+/// Marks the characters at the given indices in the text with `mark`.
+fn highlight_with(text: &str, indices: &[usize], mark: impl Fn(char) -> String) -> String {
+    text.chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if indices.contains(&index) {
+                mark(c)
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn comment(task: &str, content: &str) -> Comment {
+        Comment {
+            id: "1".to_string(),
+            item_id: Some(task.to_string()),
+            project_id: None,
+            posted_at: Utc::now(),
+            content: content.to_string(),
+            attachment: None,
+        }
+    }
+
+    #[test]
+    fn ranks_matches() {
+        let tasks = [
+            Tree::new(Task::new("1", "Buy groceries")),
+            Tree::new(Task {
+                description: "Notes\n  write the report  ".to_string(),
+                ..Task::new("2", "Quarterly numbers")
+            }),
+            Tree::new(Task::new("3", "Write report")),
+            Tree::new(Task::new("4", "Call mom")),
+        ];
+        let comments = [comment("4", "ask about the report")];
+        let mut task_comments = HashMap::new();
+        task_comments.insert(&tasks[3].id, comments.iter().collect());
+
+        let matches = rank(&tasks, &HashMap::new(), "report");
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.task.id.as_str(), m.field, m.text))
+                .collect::<Vec<_>>(),
+            vec![
+                ("3", Field::Content, "Write report"),
+                ("2", Field::Description, "write the report"),
+            ]
+        );
+        assert_eq!(matches[0].indices, vec![6, 7, 8, 9, 10, 11]);
+
+        let matches = rank(&tasks, &task_comments, "report");
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[2].field, Field::Comment);
+        assert_eq!(matches[2].text, "ask about the report");
+
+        let matches = rank(&tasks, &task_comments, "grcrs");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].task.id, "1");
+        assert!(rank(&tasks, &task_comments, "xyz").is_empty());
+    }
+
+    #[test]
+    fn highlights() {
+        let mark = |c| format!("[{c}]");
+        assert_eq!(
+            highlight_with("Write report", &[0, 6, 11], mark),
+            "[W]rite [r]epor[t]"
+        );
+        assert_eq!(highlight_with("Größe", &[2, 3], mark), "Gr[ö][ß]e");
+        assert_eq!(highlight_with("Write", &[], mark), "Write");
+    }
+}
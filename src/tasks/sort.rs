@@ -0,0 +1,112 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use crate::api::{
+    rest::{DueDate, Project, ProjectID, Task},
+    tree::Tree,
+};
+
+/// Fields that task lists can be sorted by.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SortBy {
+    Due,
+    Priority,
+    Added,
+    Content,
+    Project,
+}
+
+impl SortBy {
+    /// Compares two tasks by this field, falling back to the default order of tasks.
+    fn cmp(&self, a: &Task, b: &Task, projects: &HashMap<ProjectID, Project>) -> Ordering {
+        let ordering = match self {
+            // Tasks without a due date come last.
+            SortBy::Due => match (&a.due, &b.due) {
+                (Some(a), Some(b)) => a.date.cmp(&b.date).then_with(|| {
+                    let datetime = |due: &DueDate| due.exact.as_ref().map(|e| e.datetime);
+                    // Tasks with an exact time come first within a day.
+                    match (datetime(a), datetime(b)) {
+                        (Some(a), Some(b)) => a.cmp(&b),
+                        (a, b) => b.is_some().cmp(&a.is_some()),
+                    }
+                }),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            },
+            SortBy::Priority => b.priority.cmp(&a.priority),
+            SortBy::Added => a.added_at.cmp(&b.added_at),
+            SortBy::Content => a.content.to_lowercase().cmp(&b.content.to_lowercase()),
+            SortBy::Project => {
+                let name = |t: &Task| projects.get(&t.project_id).map(|p| p.name.to_lowercase());
+                name(a).cmp(&name(b))
+            }
+        };
+        ordering.then_with(|| a.cmp(b))
+    }
+}
+
+/// Sorts the tasks and all their subtasks, so that subtasks stay under their parent.
+///
+/// Uses the default order of tasks if no field to sort by is given.
+pub fn sort(
+    tasks: &mut [Tree<Task>],
+    by: Option<SortBy>,
+    reverse: bool,
+    projects: &HashMap<ProjectID, Project>,
+) {
+    tasks.sort_by(|a, b| {
+        let ordering = match by {
+            Some(by) => by.cmp(a, b, projects),
+            None => a.cmp(b),
+        };
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    for task in tasks {
+        sort(&mut task.subitems, by, reverse, projects);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::api::rest::Priority;
+
+    use super::*;
+
+    fn ids(tasks: &[Tree<Task>]) -> Vec<&str> {
+        tasks
+            .iter()
+            .flat_map(Tree::flatten)
+            .map(|t| t.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn sorts_recursively() {
+        let mut tasks = Tree::from_items(vec![
+            Task {
+                priority: Priority::High,
+                ..Task::new("1", "banana")
+            },
+            Task {
+                parent_id: Some("1".to_string()),
+                ..Task::new("2", "zucchini")
+            },
+            Task {
+                parent_id: Some("1".to_string()),
+                priority: Priority::Urgent,
+                ..Task::new("3", "apple")
+            },
+            Task::new("4", "Apricot"),
+        ])
+        .unwrap();
+        let projects = HashMap::new();
+        sort(&mut tasks, Some(SortBy::Content), false, &projects);
+        assert_eq!(ids(&tasks), vec!["4", "1", "3", "2"]);
+        sort(&mut tasks, Some(SortBy::Content), true, &projects);
+        assert_eq!(ids(&tasks), vec!["1", "2", "3", "4"]);
+        sort(&mut tasks, Some(SortBy::Priority), false, &projects);
+        assert_eq!(ids(&tasks), vec!["1", "3", "2", "4"]);
+    }
+}
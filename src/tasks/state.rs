@@ -1,20 +1,24 @@
-use std::collections::HashMap;
+use std::{cell::OnceCell, collections::HashMap};
 
+use chrono::Utc;
 use color_eyre::{Result, eyre::WrapErr, eyre::eyre};
-use owo_colors::OwoColorize;
+use tokio::task::JoinSet;
 
 use crate::{
     api::{
         rest::{
-            FullTask, Gateway, Label, Project, ProjectID, Section, SectionID, TableTask, Task,
-            TaskID,
+            FullTask, Gateway, Label, Project, ProjectID, ResolvedTask, Section, SectionID,
+            TableTask, Task, TaskID,
         },
         tree::{Tree, TreeFlattenExt},
     },
     config::Config,
     interactive,
+    query::{Context, Query},
 };
 
+use super::template::{Template, TemplateTask};
+
 /// State is a helper to fully construct a tasks state for display.
 pub struct State<'a> {
     pub tasks: Vec<Tree<Task>>,
@@ -22,13 +26,10 @@ pub struct State<'a> {
     pub sections: HashMap<SectionID, Section>,
     pub labels: HashMap<String, Label>,
     pub config: &'a Config,
-}
-
-// TaskMenu is used for the more complex fully interactive task creation.
-pub enum TaskMenu<'a> {
-    Menu,
-    Select(&'a Tree<Task>),
-    None,
+    /// If set, task rows are displayed using this template instead of [`TableTask`].
+    pub template: Option<Template>,
+    /// The template of [`Config::default_format`], which is only resolved once a row is shown.
+    pub(super) default_template: OnceCell<Option<Template>>,
 }
 
 impl<'a> State<'a> {
@@ -39,30 +40,123 @@ impl<'a> State<'a> {
     ) -> Result<State<'a>> {
         let (filtered_tasks, projects, sections, labels) =
             tokio::try_join!(gw.tasks(filter), gw.projects(), gw.sections(), gw.labels())?;
+        let tasks = Tree::from_items(filtered_tasks).wrap_err("tasks do not form clean tree")?;
+        Self::new(tasks, projects, sections, labels, cfg)
+    }
+    /// Fetches the tasks of each query separately, returning a state for each query in the same
+    /// order.
+    ///
+    /// If `expand` is set, all parents of the tasks are kept, even if they don't match the query.
+    pub async fn fetch_queries(
+        queries: &[&str],
+        expand: bool,
+        gw: &'_ Gateway,
+        cfg: &'a Config,
+    ) -> Result<Vec<State<'a>>> {
+        if expand {
+            let (full_state, tasks) = tokio::try_join!(
+                Self::fetch_tree(Some("all"), gw, cfg),
+                query_tasks(queries, gw)
+            )?;
+            return Ok(tasks
+                .into_iter()
+                .map(|tasks| {
+                    full_state.with_tasks(
+                        full_state
+                            .tasks
+                            .clone()
+                            .keep_trees(&tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>()),
+                    )
+                })
+                .collect());
+        }
+        let (tasks, projects, sections, labels) = tokio::try_join!(
+            query_tasks(queries, gw),
+            gw.projects(),
+            gw.sections(),
+            gw.labels()
+        )?;
+        let state = Self::new(Vec::new(), projects, sections, labels, cfg)?;
+        tasks
+            .into_iter()
+            .map(|tasks| {
+                Ok(state
+                    .with_tasks(Tree::from_items(tasks).wrap_err("tasks do not form clean tree")?))
+            })
+            .collect()
+    }
+
+    /// Fetches all tasks and filters them with each query locally instead of through the API,
+    /// returning a state for each query in the same order.
+    ///
+    /// If `expand` is set, all parents of matching tasks are kept as well.
+    pub async fn fetch_local(
+        queries: &[Query],
+        expand: bool,
+        gw: &'_ Gateway,
+        cfg: &'a Config,
+    ) -> Result<Vec<State<'a>>> {
+        let state = Self::fetch_tree(Some("all"), gw, cfg).await?;
+        let ctx = Context {
+            projects: &state.projects,
+            sections: &state.sections,
+            now: cfg.override_time.unwrap_or_else(Utc::now),
+        };
+        queries
+            .iter()
+            .map(|query| {
+                let tasks = state
+                    .tasks
+                    .flat_tree()
+                    .into_iter()
+                    .filter(|t| query.matches(t, &ctx))
+                    .map(|t| t.item.clone())
+                    .collect::<Vec<_>>();
+                Ok(state.with_tasks(if expand {
+                    state
+                        .tasks
+                        .clone()
+                        .keep_trees(&tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>())
+                } else {
+                    Tree::from_items(tasks).wrap_err("tasks do not form clean tree")?
+                }))
+            })
+            .collect()
+    }
+
+    fn new(
+        tasks: Vec<Tree<Task>>,
+        projects: Vec<Project>,
+        sections: Vec<Section>,
+        labels: Vec<Label>,
+        cfg: &'a Config,
+    ) -> Result<State<'a>> {
         let projects = projects.into_iter().map(|p| (p.id.clone(), p)).collect();
         let sections = sections.into_iter().map(|s| (s.id.clone(), s)).collect();
         // We save by name so it works with the shared labels concept of todoist
         let labels = labels.into_iter().map(|l| (l.name.clone(), l)).collect();
-        let tasks = Tree::from_items(filtered_tasks).wrap_err("tasks do not form clean tree")?;
         Ok(State {
             tasks,
             projects,
             sections,
             labels,
             config: cfg,
+            template: None,
+            default_template: OnceCell::new(),
         })
     }
-    pub async fn fetch_full_tree(
-        filter: Option<&'_ str>,
-        gw: &'_ Gateway,
-        cfg: &'a Config,
-    ) -> Result<State<'a>> {
-        let (mut full_state, tasks) =
-            tokio::try_join!(Self::fetch_tree(Some("all"), gw, cfg), gw.tasks(filter))?;
-        full_state.tasks = full_state
-            .tasks
-            .keep_trees(&tasks.iter().map(|t| t.id.clone()).collect::<Vec<_>>());
-        Ok(full_state)
+
+    /// Returns a state with the same projects, sections and labels, but other tasks.
+    fn with_tasks(&self, tasks: Vec<Tree<Task>>) -> State<'a> {
+        State {
+            tasks,
+            projects: self.projects.clone(),
+            sections: self.sections.clone(),
+            labels: self.labels.clone(),
+            config: self.config,
+            template: self.template.clone(),
+            default_template: self.default_template.clone(),
+        }
     }
 
     pub fn task(&self, id: &TaskID) -> Option<&Tree<Task>> {
@@ -76,27 +170,11 @@ impl<'a> State<'a> {
         let items = self.tasks.flat_tree();
         let result = interactive::select(
             "Select task",
-            &items.iter().map(|t| self.table_task(t)).collect::<Vec<_>>(),
+            &items.iter().map(|t| self.row(t)).collect::<Vec<_>>(),
         )?;
         Ok(result.map(|index| items[index]))
     }
 
-    pub fn select_or_menu(&'a self) -> Result<TaskMenu<'a>> {
-        let items = self.tasks.flat_tree();
-        let result = interactive::select(
-            "Select task",
-            &[format!("{} {}", ">".green(), "Action...".bold())]
-                .into_iter()
-                .chain(items.iter().map(|t| self.table_task(t).to_string()))
-                .collect::<Vec<_>>(),
-        )?;
-        match result {
-            Some(0) => Ok(TaskMenu::Menu),
-            Some(index) => Ok(TaskMenu::Select(items[index - 1])),
-            None => Ok(TaskMenu::None),
-        }
-    }
-
     pub fn filter<F>(self, filter: F) -> State<'a>
     where
         F: Fn(&Tree<Task>) -> bool,
@@ -108,6 +186,8 @@ impl<'a> State<'a> {
             sections: self.sections,
             labels: self.labels,
             config: self.config,
+            template: self.template,
+            default_template: self.default_template,
         }
     }
 
@@ -138,6 +218,43 @@ impl<'a> State<'a> {
         )
     }
 
+    /// Returns a task formatted as a row in a list, using the template if one is set.
+    pub fn row(&self, task: &Tree<Task>) -> String {
+        match self.template() {
+            Some(template) => TemplateTask(template, self.table_task(task)).to_string(),
+            None => self.table_task(task).to_string(),
+        }
+    }
+
+    /// Returns the template that was set, or otherwise the default format from the config. An
+    /// invalid default format is reported once and rows are shown as [`TableTask`] instead, so it
+    /// doesn't break commands.
+    fn template(&self) -> Option<&Template> {
+        self.template.as_ref().or_else(|| {
+            self.default_template
+                .get_or_init(|| {
+                    Template::resolve(None, self.config)
+                        .inspect_err(|e| eprintln!("warning: ignoring default_format: {e:#}"))
+                        .ok()
+                        .flatten()
+                })
+                .as_ref()
+        })
+    }
+
+    pub fn resolved_task<'s>(&'s self, task: &'s Task) -> ResolvedTask<'s> {
+        ResolvedTask {
+            task,
+            project_name: self.projects.get(&task.project_id).map(|p| p.name.as_str()),
+            section_name: task
+                .section_id
+                .as_ref()
+                .and_then(|s| self.sections.get(s))
+                .map(|s| s.name.as_str()),
+            comments: None,
+        }
+    }
+
     pub fn full_task<'s>(&'s self, task: &'s Tree<Task>) -> FullTask<'s> {
         FullTask(
             task,
@@ -148,3 +265,20 @@ impl<'a> State<'a> {
         )
     }
 }
+
+/// Fetches the tasks of each query concurrently, in the order of the queries.
+async fn query_tasks(queries: &[&str], gw: &Gateway) -> Result<Vec<Vec<Task>>> {
+    let mut running = JoinSet::new();
+    for (index, query) in queries.iter().enumerate() {
+        let gw = gw.clone();
+        let query = query.to_string();
+        running.spawn(async move { (index, gw.tasks(Some(&query)).await) });
+    }
+    let mut results = Vec::with_capacity(queries.len());
+    while let Some(joined) = running.join_next().await {
+        let (index, tasks) = joined.wrap_err("fetching tasks was aborted")?;
+        results.push((index, tasks?));
+    }
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, tasks)| tasks).collect())
+}
@@ -0,0 +1,389 @@
+//! Templates allow to customize how a single task row is displayed.
+//!
+//! A template is text with fields in braces, like `{id} {priority} {content}`. Each field can
+//! take directives after a colon, separated by more colons:
+//!
+//! * `30` pads the field to 30 characters, `>30` pads it on the left instead.
+//! * `.20` truncates the field to 20 characters.
+//! * `30.30` does both at once.
+//! * A color or style such as `red`, `bright_blue` or `bold` overrides the default color of
+//!   the field, and `plain` removes it.
+//!
+//! Literal braces are written as `{{` and `}}`.
+use std::str::FromStr;
+
+use chrono::Utc;
+use color_eyre::{
+    Result,
+    eyre::{WrapErr, eyre},
+};
+use owo_colors::{OwoColorize, Stream, Style};
+use strum::{Display, EnumString, VariantNames};
+
+use crate::{
+    api::rest::{DueDateFormatter, Priority, TableTask},
+    config::Config,
+};
+
+/// All fields that can be used in a template.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Display, EnumString, VariantNames)]
+#[strum(serialize_all = "lowercase")]
+pub enum Field {
+    Id,
+    Priority,
+    Content,
+    Description,
+    Project,
+    Section,
+    Labels,
+    Due,
+    Url,
+}
+
+/// Describes how a single field is formatted.
+#[derive(Debug, Clone, PartialEq)]
+struct Spec {
+    field: Field,
+    width: Option<usize>,
+    align_right: bool,
+    truncate: Option<usize>,
+    style: Option<Style>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(Spec),
+}
+
+/// Template is a parsed task row format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template(Vec<Segment>);
+
+impl Template {
+    /// Returns the template that is used to display task rows with the given config. `format` can
+    /// be either the name of a format in [`Config::formats`] or a template itself. Without a
+    /// format, [`Config::default_format`] is used if set.
+    pub fn resolve(format: Option<&str>, cfg: &Config) -> Result<Option<Template>> {
+        let format = match format.or(cfg.default_format.as_deref()) {
+            Some(format) => format,
+            None => return Ok(None),
+        };
+        let template = cfg
+            .formats
+            .get(format)
+            .map(String::as_str)
+            .unwrap_or(format);
+        Ok(Some(template.parse().wrap_err_with(|| {
+            format!("invalid format '{template}'")
+        })?))
+    }
+}
+
+impl FromStr for Template {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut field = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => field.push(c),
+                            None => return Err(eyre!("unclosed '{{' in format")),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field.parse()?));
+                }
+                '}' => return Err(eyre!("unmatched '}}' in format, use '}}}}' for a literal")),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Template(segments))
+    }
+}
+
+impl FromStr for Spec {
+    type Err = color_eyre::eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default().trim();
+        let field = name.parse().map_err(|_| {
+            eyre!(
+                "unknown field '{}', expected one of: {}",
+                name,
+                Field::VARIANTS.join(", ")
+            )
+        })?;
+        let mut spec = Spec {
+            field,
+            width: None,
+            align_right: false,
+            truncate: None,
+            style: None,
+        };
+        for directive in parts.map(str::trim) {
+            if directive.starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '>') {
+                let directive = match directive.strip_prefix('>') {
+                    Some(d) => {
+                        spec.align_right = true;
+                        d
+                    }
+                    None => directive,
+                };
+                let (width, truncate) = match directive.split_once('.') {
+                    Some((width, truncate)) => (width, Some(truncate)),
+                    None => (directive, None),
+                };
+                let number = |n: &str| {
+                    n.parse::<usize>()
+                        .map_err(|_| eyre!("invalid width '{}' for field '{}'", directive, name))
+                };
+                if !width.is_empty() {
+                    spec.width = Some(number(width)?);
+                }
+                if let Some(truncate) = truncate {
+                    spec.truncate = Some(number(truncate)?);
+                }
+            } else {
+                spec.style = Some(style(spec.style.unwrap_or_default(), directive)?);
+            }
+        }
+        Ok(spec)
+    }
+}
+
+/// Adds the named color or style to the given style.
+fn style(style: Style, name: &str) -> Result<Style> {
+    Ok(match name {
+        "plain" => Style::new(),
+        "bold" => style.bold(),
+        "dimmed" => style.dimmed(),
+        "italic" => style.italic(),
+        "underline" => style.underline(),
+        "black" => style.black(),
+        "red" => style.red(),
+        "green" => style.green(),
+        "yellow" => style.yellow(),
+        "blue" => style.blue(),
+        "magenta" => style.magenta(),
+        "cyan" => style.cyan(),
+        "white" => style.white(),
+        "bright_black" => style.bright_black(),
+        "bright_red" => style.bright_red(),
+        "bright_green" => style.bright_green(),
+        "bright_yellow" => style.bright_yellow(),
+        "bright_blue" => style.bright_blue(),
+        "bright_magenta" => style.bright_magenta(),
+        "bright_cyan" => style.bright_cyan(),
+        "bright_white" => style.bright_white(),
+        _ => return Err(eyre!("unknown color or style '{}'", name)),
+    })
+}
+
+/// Returns the plain text of a field and the style it is displayed with by default.
+fn value(field: Field, table: &TableTask<'_>) -> (String, Style) {
+    let TableTask(task, project, section, labels, config) = table;
+    match field {
+        Field::Id => (task.id.clone(), Style::new().bright_yellow()),
+        Field::Priority => (
+            task.priority.label().to_string(),
+            match task.priority {
+                Priority::Normal => Style::new(),
+                Priority::High => Style::new().blue(),
+                Priority::VeryHigh => Style::new().yellow(),
+                Priority::Urgent => Style::new().red(),
+            },
+        ),
+        Field::Content => (task.content.clone(), Style::new()),
+        Field::Description => (task.description.clone(), Style::new()),
+        Field::Project => (
+            project.map(|p| p.name.clone()).unwrap_or_default(),
+            Style::new(),
+        ),
+        Field::Section => (
+            section.map(|s| s.name.clone()).unwrap_or_default(),
+            Style::new(),
+        ),
+        Field::Labels => (
+            labels
+                .iter()
+                .map(|l| format!("@{}", l.name))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Style::new().bright_blue(),
+        ),
+        Field::Due => match &task.due {
+            Some(due) => {
+                let now = config.override_time.unwrap_or_else(Utc::now);
+                let due = DueDateFormatter(due, &now);
+                let style = if due.overdue() {
+                    Style::new().bright_red()
+                } else {
+                    Style::new().bright_green()
+                };
+                (due.text(), style)
+            }
+            None => (String::new(), Style::new()),
+        },
+        Field::Url => (task.url.to_string(), Style::new()),
+    }
+}
+
+impl Spec {
+    fn write(&self, f: &mut std::fmt::Formatter<'_>, table: &TableTask<'_>) -> std::fmt::Result {
+        let (mut text, default_style) = value(self.field, table);
+        if let Some(truncate) = self.truncate
+            && text.chars().count() > truncate
+        {
+            text = text.chars().take(truncate.saturating_sub(1)).collect();
+            if truncate > 0 {
+                text.push('…');
+            }
+        }
+        if let Some(width) = self.width {
+            let padding = " ".repeat(width.saturating_sub(text.chars().count()));
+            if self.align_right {
+                text.insert_str(0, &padding);
+            } else {
+                text.push_str(&padding);
+            }
+        }
+        let style = self.style.unwrap_or(default_style);
+        write!(
+            f,
+            "{}",
+            text.if_supports_color(Stream::Stdout, |text| text.style(style))
+        )
+    }
+}
+
+/// Used to display a task as an item in a list by using a [`Template`].
+pub struct TemplateTask<'a>(pub &'a Template, pub TableTask<'a>);
+
+impl std::fmt::Display for TemplateTask<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let TemplateTask(Template(segments), table) = self;
+        if table.0.depth > 0 {
+            write!(f, "{}⌞ ", "  ".repeat(table.0.depth))?;
+        }
+        for segment in segments {
+            match segment {
+                Segment::Literal(text) => f.write_str(text)?,
+                Segment::Field(spec) => spec.write(f, table)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::api::{
+        rest::{Label, Project, Section, Task},
+        tree::Tree,
+    };
+
+    fn render(template: &str, task: &Tree<Task>) -> String {
+        let cfg = Config::default();
+        let project = Project::new("1", "project");
+        let section = Section::new("2", "1", "section");
+        let label = Label::new("3", "label");
+        let template: Template = template.parse().unwrap();
+        TemplateTask(
+            &template,
+            TableTask(task, Some(&project), Some(&section), vec![&label], &cfg),
+        )
+        .to_string()
+    }
+
+    #[test]
+    fn renders_fields() {
+        let task = Tree::new(Task {
+            priority: Priority::Urgent,
+            ..Task::new("123", "hello")
+        });
+        assert_eq!(
+            render(
+                "{id} {priority} {content} [{project}/{section}] {labels}",
+                &task
+            ),
+            "123 p1 hello [project/section] @label"
+        );
+        assert_eq!(render("{{{id}}}", &task), "{123}");
+    }
+
+    #[test]
+    fn renders_directives() {
+        let task = Tree::new(Task::new("123", "hello there"));
+        assert_eq!(render("{id:6}|", &task), "123   |");
+        assert_eq!(render("{id:>6}|", &task), "   123|");
+        assert_eq!(render("{content:.5}|", &task), "hell…|");
+        assert_eq!(render("{content:8.5:bold}|", &task), "hell…   |");
+        assert_eq!(render("{content:.20:red}|", &task), "hello there|");
+    }
+
+    #[test]
+    fn renders_subtasks() {
+        let mut task = Tree::new(Task::new("123", "hello"));
+        task.depth = 1;
+        assert_eq!(render("{content}", &task), "  ⌞ hello");
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!("{id".parse::<Template>().is_err());
+        assert!("id}".parse::<Template>().is_err());
+        assert!("{nope}".parse::<Template>().is_err());
+        assert!("{id:rainbow}".parse::<Template>().is_err());
+        assert!("{id:3x}".parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn resolves_named_formats() {
+        let cfg = Config {
+            formats: HashMap::from([("short".to_string(), "{id}".to_string())]),
+            ..Default::default()
+        };
+        assert_eq!(
+            Template::resolve(Some("short"), &cfg).unwrap(),
+            Some("{id}".parse().unwrap())
+        );
+        assert_eq!(
+            Template::resolve(Some("{content}"), &cfg).unwrap(),
+            Some("{content}".parse().unwrap())
+        );
+        assert_eq!(Template::resolve(None, &cfg).unwrap(), None);
+        let cfg = Config {
+            default_format: Some("short".to_string()),
+            ..cfg
+        };
+        assert_eq!(
+            Template::resolve(None, &cfg).unwrap(),
+            Some("{id}".parse().unwrap())
+        );
+    }
+}
@@ -13,6 +13,17 @@ pub struct Params {
 /// Displays full information about a task.
 pub async fn view(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
     let (id, state) = params.task.task(gw, cfg).await?;
+    if !cfg.output.is_text() {
+        let task = state.task(&id).ok_or_else(|| eyre!("no valid task"))?;
+        let comments = if task.note_count > 0 {
+            gw.task_comments(&id).await?
+        } else {
+            Vec::new()
+        };
+        let mut task = state.resolved_task(task);
+        task.comments = Some(&comments);
+        return cfg.output.item(&task);
+    }
     let task = state.full_task(state.task(&id).ok_or_else(|| eyre!("no valid task"))?);
     println!("{task}");
     if task.0.note_count > 0 {
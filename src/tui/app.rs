@@ -0,0 +1,621 @@
+use std::collections::{HashMap, HashSet};
+
+use color_eyre::Result;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::{
+    api::{
+        rest::{
+            Comment, CreateTask, FullTask, Gateway, Label, MoveTask, Project, ProjectID, Section,
+            Task, TaskDue, TaskID, UpdateTask,
+        },
+        tree::{Tree, TreeFlattenExt},
+    },
+    config::Config,
+    interactive::Ambiguity,
+    tasks::{
+        Priority,
+        inline::{Defaults, Inline},
+    },
+};
+
+/// Everything that is fetched from the API to display the UI.
+pub struct Data {
+    /// All active tasks.
+    pub tasks: Vec<Task>,
+    /// IDs of the tasks that pass the filter.
+    pub filtered: HashSet<TaskID>,
+    pub projects: Vec<Project>,
+    pub sections: Vec<Section>,
+    pub labels: Vec<Label>,
+}
+
+impl Data {
+    pub async fn fetch(filter: &str, gw: &Gateway) -> Result<Data> {
+        let (tasks, filtered, projects, sections, labels) = tokio::try_join!(
+            gw.tasks(None),
+            gw.tasks(Some(filter)),
+            gw.projects(),
+            gw.sections(),
+            gw.labels()
+        )?;
+        Ok(Data {
+            tasks,
+            filtered: filtered.into_iter().map(|t| t.id).collect(),
+            projects,
+            sections,
+            labels,
+        })
+    }
+}
+
+/// The tasks that are shown in the task pane, as selected in the sidebar.
+#[derive(Debug, Clone, PartialEq)]
+pub enum View {
+    Filter(String),
+    Project(ProjectID),
+    Label(String),
+}
+
+/// The pane that receives navigation keys.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Focus {
+    Sidebar,
+    Tasks,
+}
+
+/// What the text that is being typed in is used for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Input {
+    Add,
+    Subtask,
+    Rename,
+    Due,
+}
+
+/// Describes what keys are currently used for.
+#[derive(Debug, PartialEq)]
+pub enum Mode {
+    Normal,
+    Input {
+        kind: Input,
+        text: String,
+    },
+    Priority,
+    Move {
+        targets: Vec<(String, MoveTask)>,
+        selected: usize,
+    },
+}
+
+/// Changes that the key presses of the user want to make through the API.
+#[derive(Debug, PartialEq)]
+pub enum Action {
+    Close(TaskID),
+    Update(TaskID, UpdateTask),
+    Move(TaskID, MoveTask),
+    Add(CreateTask),
+    Refresh,
+}
+
+pub struct App {
+    pub data: Data,
+    /// Entries of the sidebar with the title they're displayed with.
+    pub sidebar: Vec<(View, String)>,
+    /// Index of the sidebar entry that is currently shown.
+    pub view: usize,
+    pub focus: Focus,
+    pub tasks: Vec<Tree<Task>>,
+    /// Index of the selected row in [`App::visible`].
+    pub selected: usize,
+    pub comments: HashMap<TaskID, Vec<Comment>>,
+    pub mode: Mode,
+    /// Message shown at the bottom, like the result of the last action.
+    pub status: String,
+    pub quit: bool,
+    collapsed: HashSet<TaskID>,
+}
+
+impl App {
+    pub fn new(filter: &str, data: Data) -> App {
+        let mut app = App {
+            data,
+            sidebar: vec![(View::Filter(filter.to_string()), filter.to_string())],
+            view: 0,
+            focus: Focus::Tasks,
+            tasks: Vec::new(),
+            selected: 0,
+            comments: HashMap::new(),
+            mode: Mode::Normal,
+            status: String::new(),
+            quit: false,
+            collapsed: HashSet::new(),
+        };
+        app.rebuild();
+        app
+    }
+
+    /// Replaces the data with a newer version, keeping the current view and selection.
+    pub fn update(&mut self, data: Data) {
+        self.data = data;
+        self.comments.clear();
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        let selected = self.selected_task().map(|t| t.id.clone());
+        let view = self.sidebar[self.view].0.clone();
+        let filter = self.sidebar[0].clone();
+        self.sidebar = vec![filter];
+        let projects = Tree::from_items(self.data.projects.clone()).unwrap_or_default();
+        self.sidebar
+            .extend(projects.flat_tree().into_iter().map(|p| {
+                (
+                    View::Project(p.id.clone()),
+                    format!("{}#{}", "  ".repeat(p.depth), p.name),
+                )
+            }));
+        self.sidebar.extend(
+            self.data
+                .labels
+                .iter()
+                .map(|l| (View::Label(l.name.clone()), format!("@{}", l.name))),
+        );
+        self.view = self
+            .sidebar
+            .iter()
+            .position(|(v, _)| *v == view)
+            .unwrap_or_default();
+
+        let tasks = self
+            .data
+            .tasks
+            .iter()
+            .filter(|task| match &view {
+                View::Filter(_) => self.data.filtered.contains(&task.id),
+                View::Project(id) => task.project_id == *id,
+                View::Label(name) => task.labels.contains(name),
+            })
+            .cloned()
+            .collect();
+        // Parents that aren't part of the view are dropped, so this can't fail.
+        self.tasks = Tree::from_items(tasks).unwrap_or_default();
+        self.selected = selected
+            .and_then(|id| self.visible().iter().position(|t| t.id == id))
+            .unwrap_or(self.selected)
+            .min(self.visible().len().saturating_sub(1));
+    }
+
+    /// Returns the rows of the task pane, without the subtasks of collapsed tasks.
+    pub fn visible(&self) -> Vec<&Tree<Task>> {
+        fn visible<'a>(
+            tasks: &'a [Tree<Task>],
+            collapsed: &HashSet<TaskID>,
+        ) -> Vec<&'a Tree<Task>> {
+            let mut tasks = tasks.iter().collect::<Vec<_>>();
+            tasks.sort();
+            tasks
+                .into_iter()
+                .flat_map(|task| {
+                    let mut rows = vec![task];
+                    if !collapsed.contains(&task.id) {
+                        rows.extend(visible(&task.subitems, collapsed));
+                    }
+                    rows
+                })
+                .collect()
+        }
+        visible(&self.tasks, &self.collapsed)
+    }
+
+    pub fn is_collapsed(&self, task: &Task) -> bool {
+        self.collapsed.contains(&task.id)
+    }
+
+    pub fn selected_task(&self) -> Option<&Tree<Task>> {
+        self.visible().get(self.selected).copied()
+    }
+
+    /// Returns the selected task if its comments still need to be fetched.
+    pub fn missing_comments(&self) -> Option<TaskID> {
+        self.selected_task()
+            .filter(|t| t.note_count > 0 && !self.comments.contains_key(&t.id))
+            .map(|t| t.id.clone())
+    }
+
+    pub fn full_task<'a>(&'a self, task: &'a Task, cfg: &'a Config) -> FullTask<'a> {
+        FullTask(
+            task,
+            self.data.projects.iter().find(|p| p.id == task.project_id),
+            task.section_id
+                .as_ref()
+                .and_then(|id| self.data.sections.iter().find(|s| s.id == *id)),
+            self.data
+                .labels
+                .iter()
+                .filter(|l| task.labels.contains(&l.name))
+                .collect(),
+            cfg,
+        )
+    }
+
+    /// Handles a key press, returning an action if something should be changed through the API.
+    pub fn key(&mut self, key: KeyEvent) -> Option<Action> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.quit = true;
+            return None;
+        }
+        // Messages only stay until the next key press, so the key help is shown again.
+        self.status.clear();
+        match std::mem::replace(&mut self.mode, Mode::Normal) {
+            Mode::Normal => self.key_normal(key),
+            Mode::Input { kind, mut text } => match key.code {
+                KeyCode::Enter => self.submit(kind, text),
+                KeyCode::Esc => None,
+                KeyCode::Backspace => {
+                    text.pop();
+                    self.mode = Mode::Input { kind, text };
+                    None
+                }
+                KeyCode::Char(c) => {
+                    text.push(c);
+                    self.mode = Mode::Input { kind, text };
+                    None
+                }
+                _ => {
+                    self.mode = Mode::Input { kind, text };
+                    None
+                }
+            },
+            Mode::Priority => {
+                let priority = match key.code {
+                    KeyCode::Char(c) => c
+                        .to_digit(10)
+                        .and_then(|d| Priority::try_from(d as usize).ok()),
+                    _ => None,
+                }?;
+                Some(Action::Update(
+                    self.selected_task()?.id.clone(),
+                    UpdateTask {
+                        priority: Some(priority.into()),
+                        ..Default::default()
+                    },
+                ))
+            }
+            Mode::Move {
+                mut targets,
+                selected,
+            } => match key.code {
+                KeyCode::Enter => Some(Action::Move(
+                    self.selected_task()?.id.clone(),
+                    targets.swap_remove(selected).1,
+                )),
+                KeyCode::Esc => None,
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let selected = (selected + 1).min(targets.len() - 1);
+                    self.mode = Mode::Move { targets, selected };
+                    None
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let selected = selected.saturating_sub(1);
+                    self.mode = Mode::Move { targets, selected };
+                    None
+                }
+                _ => {
+                    self.mode = Mode::Move { targets, selected };
+                    None
+                }
+            },
+        }
+    }
+
+    fn key_normal(&mut self, key: KeyEvent) -> Option<Action> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = match self.focus {
+                    Focus::Sidebar => Focus::Tasks,
+                    Focus::Tasks => Focus::Sidebar,
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.navigate(1),
+            KeyCode::Up | KeyCode::Char('k') => self.navigate(-1),
+            KeyCode::Char('r') => return Some(Action::Refresh),
+            KeyCode::Char('a') => self.input(Input::Add, String::new()),
+            _ if self.focus == Focus::Sidebar => {}
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                let id = self.selected_task()?.id.clone();
+                if !self.collapsed.remove(&id) {
+                    self.collapsed.insert(id);
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                let id = self.selected_task()?.id.clone();
+                self.collapsed.insert(id);
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                let id = self.selected_task()?.id.clone();
+                self.collapsed.remove(&id);
+            }
+            KeyCode::Char('c') => return Some(Action::Close(self.selected_task()?.id.clone())),
+            KeyCode::Char('A') => {
+                self.selected_task()?;
+                self.input(Input::Subtask, String::new())
+            }
+            KeyCode::Char('e') => {
+                let content = self.selected_task()?.content.clone();
+                self.input(Input::Rename, content)
+            }
+            KeyCode::Char('d') => {
+                let due = self.selected_task()?.due.as_ref().map(|d| d.string.clone());
+                self.input(Input::Due, due.unwrap_or_default())
+            }
+            KeyCode::Char('p') => {
+                self.selected_task()?;
+                self.mode = Mode::Priority;
+            }
+            KeyCode::Char('m') => {
+                self.selected_task()?;
+                match self.move_targets() {
+                    Ok(targets) if targets.is_empty() => {
+                        self.status = "no projects to move the task to".to_string()
+                    }
+                    Ok(targets) => {
+                        self.mode = Mode::Move {
+                            targets,
+                            selected: 0,
+                        }
+                    }
+                    Err(e) => self.status = format!("{e:#}"),
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn input(&mut self, kind: Input, text: String) {
+        self.mode = Mode::Input { kind, text };
+    }
+
+    fn navigate(&mut self, offset: isize) {
+        match self.focus {
+            Focus::Sidebar => {
+                self.view = self
+                    .view
+                    .saturating_add_signed(offset)
+                    .min(self.sidebar.len() - 1);
+                self.selected = 0;
+                self.rebuild();
+            }
+            Focus::Tasks => {
+                self.selected = self
+                    .selected
+                    .saturating_add_signed(offset)
+                    .min(self.visible().len().saturating_sub(1));
+            }
+        }
+    }
+
+    /// Projects and sections the selected task can be moved to, in the order of the sidebar.
+    fn move_targets(&self) -> Result<Vec<(String, MoveTask)>> {
+        let projects = Tree::from_items(self.data.projects.clone())?;
+        Ok(projects
+            .flat_tree()
+            .into_iter()
+            .flat_map(|project| {
+                let mut targets = vec![(
+                    format!("{}#{}", "  ".repeat(project.depth), project.name),
+                    MoveTask {
+                        project_id: Some(project.id.clone()),
+                        ..Default::default()
+                    },
+                )];
+                targets.extend(
+                    self.data
+                        .sections
+                        .iter()
+                        .filter(|s| s.project_id == project.id)
+                        .map(|s| {
+                            (
+                                format!("{}  /{}", "  ".repeat(project.depth), s.name),
+                                MoveTask {
+                                    section_id: Some(s.id.clone()),
+                                    ..Default::default()
+                                },
+                            )
+                        }),
+                );
+                targets
+            })
+            .collect())
+    }
+
+    fn submit(&mut self, kind: Input, text: String) -> Option<Action> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        match kind {
+            Input::Add | Input::Subtask => match self.create(kind, &text) {
+                Ok(create) => Some(Action::Add(create)),
+                Err(e) => {
+                    self.status = format!("{e:#}");
+                    None
+                }
+            },
+            Input::Rename => Some(Action::Update(
+                self.selected_task()?.id.clone(),
+                UpdateTask {
+                    content: Some(text),
+                    ..Default::default()
+                },
+            )),
+            Input::Due => Some(Action::Update(
+                self.selected_task()?.id.clone(),
+                UpdateTask {
+                    due: Some(TaskDue::String(text)),
+                    ..Default::default()
+                },
+            )),
+        }
+    }
+
+    /// Creates a task from the quick add syntax, in the project or with the label of the view.
+    fn create(&self, kind: Input, text: &str) -> Result<CreateTask> {
+        let view = &self.sidebar[self.view].0;
+        let project = match view {
+            View::Project(id) => self.data.projects.iter().find(|p| p.id == *id),
+            _ => None,
+        };
+        let labels = match view {
+            View::Label(name) => self
+                .data
+                .labels
+                .iter()
+                .position(|l| l.name == *name)
+                .map(|i| &self.data.labels[i..=i])
+                .unwrap_or_default(),
+            _ => &[],
+        };
+        let defaults = Defaults {
+            project,
+            section: None,
+            labels,
+            priority: None,
+            due: None,
+            description: None,
+        };
+        let subtask = kind == Input::Subtask;
+        let mut create = Inline::parse(text).resolve(
+            subtask,
+            &defaults,
+            &self.data.projects,
+            &self.data.sections,
+            &self.data.labels,
+            // The terminal UI can't show prompts.
+            Ambiguity::Fail,
+        )?;
+        if subtask {
+            create.parent_id = self.selected_task().map(|t| t.id.clone());
+        }
+        Ok(create)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn app() -> App {
+        let tasks = vec![
+            Task {
+                project_id: "1".to_string(),
+                ..Task::new("1", "Trip")
+            },
+            Task {
+                project_id: "1".to_string(),
+                parent_id: Some("1".to_string()),
+                ..Task::new("2", "Pack")
+            },
+            Task {
+                project_id: "2".to_string(),
+                labels: vec!["home".to_string()],
+                ..Task::new("3", "Laundry")
+            },
+        ];
+        App::new(
+            "today",
+            Data {
+                filtered: HashSet::from(["1".to_string(), "2".to_string()]),
+                tasks,
+                projects: vec![Project::new("1", "Travel"), Project::new("2", "Home")],
+                sections: vec![Section::new("1", "2", "Chores")],
+                labels: vec![Label::new("1", "home")],
+            },
+        )
+    }
+
+    fn press(app: &mut App, keys: &str) -> Option<Action> {
+        keys.chars()
+            .map(|c| app.key(KeyEvent::from(KeyCode::Char(c))))
+            .last()
+            .flatten()
+    }
+
+    #[test]
+    fn builds_views() {
+        let mut app = app();
+        assert_eq!(
+            app.sidebar
+                .iter()
+                .map(|(_, t)| t.as_str())
+                .collect::<Vec<_>>(),
+            vec!["today", "#Travel", "#Home", "@home"]
+        );
+        assert_eq!(app.visible().len(), 2);
+        app.focus = Focus::Sidebar;
+        press(&mut app, "jj");
+        assert_eq!(app.view, 2);
+        assert_eq!(app.visible()[0].content, "Laundry");
+        press(&mut app, "j");
+        assert_eq!(app.visible()[0].content, "Laundry");
+    }
+
+    #[test]
+    fn collapses_tasks() {
+        let mut app = app();
+        press(&mut app, "h");
+        assert_eq!(app.visible().len(), 1);
+        press(&mut app, " ");
+        assert_eq!(app.visible().len(), 2);
+        press(&mut app, "j");
+        assert_eq!(app.selected_task().unwrap().content, "Pack");
+    }
+
+    #[test]
+    fn move_without_targets() {
+        let mut app = app();
+        app.data.projects.clear();
+        assert_eq!(press(&mut app, "m"), None);
+        assert_eq!(app.status, "no projects to move the task to");
+        // Still in the normal mode, where the key selects the next task and clears the message.
+        assert_eq!(press(&mut app, "j"), None);
+        assert_eq!(app.selected_task().unwrap().content, "Pack");
+        assert!(app.status.is_empty());
+    }
+
+    #[test]
+    fn creates_actions() {
+        let mut app = app();
+        assert_eq!(press(&mut app, "c"), Some(Action::Close("1".to_string())));
+        assert_eq!(
+            press(&mut app, "p2"),
+            Some(Action::Update(
+                "1".to_string(),
+                UpdateTask {
+                    priority: Some(Priority::VeryHigh.into()),
+                    ..Default::default()
+                }
+            ))
+        );
+        press(&mut app, "mjj");
+        assert_eq!(
+            app.key(KeyEvent::from(KeyCode::Enter)),
+            Some(Action::Move(
+                "1".to_string(),
+                MoveTask {
+                    section_id: Some("1".to_string()),
+                    ..Default::default()
+                }
+            ))
+        );
+        press(&mut app, "AShoes @ho");
+        let Some(Action::Add(create)) = app.key(KeyEvent::from(KeyCode::Enter)) else {
+            panic!("expected a task to be added");
+        };
+        assert_eq!(create.content, "Shoes");
+        assert_eq!(create.parent_id.as_deref(), Some("1"));
+        assert_eq!(create.labels, vec!["home"]);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+}
@@ -0,0 +1,147 @@
+//! A full-screen terminal UI to browse and work with tasks.
+//!
+//! The sidebar lists the filter, projects and labels, the task pane shows the tasks of the
+//! selected entry as a tree and the detail pane shows the selected task with its comments.
+mod app;
+mod ui;
+
+use std::{thread, time::Duration};
+
+use color_eyre::{Result, eyre::WrapErr};
+use ratatui::{
+    DefaultTerminal,
+    crossterm::event::{self, Event, KeyEventKind},
+};
+use tokio::sync::mpsc;
+
+use crate::{
+    api::rest::{Comment, Gateway, TaskID},
+    config::Config,
+};
+
+use app::{Action, App, Data};
+
+#[derive(clap::Parser, Debug)]
+pub struct Params {
+    /// Filter of the first view in the sidebar. Uses the default filter if not set.
+    #[arg(short = 'f', long = "filter")]
+    filter: Option<String>,
+    /// Seconds between refreshing tasks in the background.
+    #[arg(long = "refresh", default_value_t = 30)]
+    refresh: u64,
+}
+
+/// Runs the terminal UI until the user quits.
+pub async fn tui(params: Params, gw: &Gateway, cfg: &Config) -> Result<()> {
+    let filter = params.filter.unwrap_or_else(|| cfg.default_filter.clone());
+    // Fetch before taking over the terminal, so errors like a missing token are shown normally.
+    let mut app = App::new(&filter, Data::fetch(&filter, gw).await?);
+    // Text that is shared with the rest of the CLI is colored for terminals, not the UI.
+    owo_colors::set_override(false);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut app, &filter, params.refresh, gw, cfg).await;
+    ratatui::restore();
+    owo_colors::unset_override();
+    result
+}
+
+async fn run(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    filter: &str,
+    refresh: u64,
+    gw: &Gateway,
+    cfg: &Config,
+) -> Result<()> {
+    // Terminal events are read on their own thread, as reading them blocks.
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if events_tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    let mut interval = tokio::time::interval(Duration::from_secs(refresh.max(1)));
+    interval.tick().await;
+    let mut fetch = None;
+    let mut comments = None;
+    while !app.quit {
+        if comments.is_none()
+            && let Some(id) = app.missing_comments()
+        {
+            comments = Some(Box::pin(task_comments(id, gw)));
+        }
+        terminal
+            .draw(|frame| ui::draw(frame, app, cfg))
+            .wrap_err("unable to draw terminal UI")?;
+        tokio::select! {
+            Some(event) = events.recv() => {
+                let Event::Key(key) = event else { continue };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                let Some(action) = app.key(key) else { continue };
+                match perform(action, gw).await {
+                    Ok(status) => {
+                        app.status = status;
+                        fetch = Some(Box::pin(Data::fetch(filter, gw)));
+                    }
+                    Err(e) => app.status = format!("{e:#}"),
+                }
+            }
+            _ = interval.tick(), if fetch.is_none() => {
+                fetch = Some(Box::pin(Data::fetch(filter, gw)));
+            }
+            data = async { fetch.as_mut().unwrap().await }, if fetch.is_some() => {
+                fetch = None;
+                match data {
+                    Ok(data) => app.update(data),
+                    Err(e) => app.status = format!("{e:#}"),
+                }
+            }
+            (id, result) = async { comments.as_mut().unwrap().await }, if comments.is_some() => {
+                comments = None;
+                match result {
+                    Ok(result) => {
+                        app.comments.insert(id, result);
+                    }
+                    Err(e) => {
+                        // Don't try again until the next refresh.
+                        app.comments.insert(id, Vec::new());
+                        app.status = format!("{e:#}");
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn task_comments(id: TaskID, gw: &Gateway) -> (TaskID, Result<Vec<Comment>>) {
+    let comments = gw.task_comments(&id).await;
+    (id, comments)
+}
+
+/// Makes the change of the action and returns the message to show to the user.
+async fn perform(action: Action, gw: &Gateway) -> Result<String> {
+    Ok(match action {
+        Action::Close(id) => {
+            gw.close(&id).await?;
+            "closed task".to_string()
+        }
+        Action::Update(id, update) => {
+            gw.update(&id, &update).await?;
+            "updated task".to_string()
+        }
+        Action::Move(id, task) => {
+            gw.move_task(&id, &task).await?;
+            "moved task".to_string()
+        }
+        Action::Add(create) => {
+            let task = gw.create(&create).await?;
+            format!("created task: {}", task.content)
+        }
+        Action::Refresh => "refreshing tasks".to_string(),
+    })
+}
@@ -0,0 +1,169 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::{
+    api::rest::{FullComment, Priority},
+    config::Config,
+};
+
+use super::app::{App, Focus, Input, Mode};
+
+const HELP: &str = "q quit  tab switch pane  ⏎ expand  a add  A subtask  c close  e edit  d due  p priority  m move  r refresh";
+
+pub fn draw(frame: &mut Frame, app: &App, cfg: &Config) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [sidebar, tasks, details] = Layout::horizontal([
+        Constraint::Percentage(20),
+        Constraint::Percentage(45),
+        Constraint::Percentage(35),
+    ])
+    .areas(main);
+
+    let items = app
+        .sidebar
+        .iter()
+        .map(|(_, title)| ListItem::new(title.as_str()))
+        .collect::<Vec<_>>();
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(pane("Views", app.focus == Focus::Sidebar))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        sidebar,
+        &mut ListState::default().with_selected(Some(app.view)),
+    );
+
+    let rows = app.visible();
+    let items = rows
+        .iter()
+        .map(|task| {
+            let marker = match (task.subitems.is_empty(), app.is_collapsed(task)) {
+                (true, _) => "  ",
+                (false, true) => "▸ ",
+                (false, false) => "▾ ",
+            };
+            let mut spans = vec![Span::raw(format!("{}{marker}", "  ".repeat(task.depth)))];
+            if let Some(color) = match task.priority {
+                Priority::Urgent => Some(Color::Red),
+                Priority::VeryHigh => Some(Color::LightRed),
+                Priority::High => Some(Color::Blue),
+                Priority::Normal => None,
+            } {
+                spans.push(Span::styled(
+                    format!("{} ", task.priority.label()),
+                    Style::new().fg(color),
+                ));
+            }
+            spans.push(Span::raw(task.content.as_str()));
+            if let Some(due) = &task.due {
+                spans.push(Span::styled(
+                    format!(" {}", due.string),
+                    Style::new().fg(Color::Green),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect::<Vec<_>>();
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(pane("Tasks", app.focus == Focus::Tasks))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+        tasks,
+        &mut ListState::default().with_selected((!rows.is_empty()).then_some(app.selected)),
+    );
+
+    let text = match app.selected_task() {
+        Some(task) => {
+            let mut text = app.full_task(task, cfg).to_string();
+            if let Some(comments) = app.comments.get(&task.id) {
+                for comment in comments {
+                    text.push_str(&format!("\n-----\n{}", FullComment(comment)));
+                }
+            }
+            text
+        }
+        None => "No tasks in this view".to_string(),
+    };
+    frame.render_widget(
+        Paragraph::new(text)
+            .block(pane("Details", false))
+            .wrap(Wrap { trim: false }),
+        details,
+    );
+
+    match &app.mode {
+        Mode::Input { kind, text } => {
+            let prompt = match kind {
+                Input::Add => "Add task",
+                Input::Subtask => "Add subtask",
+                Input::Rename => "Name",
+                Input::Due => "Due",
+            };
+            let line = format!("{prompt}: {text}");
+            frame.set_cursor_position((status.x + line.chars().count() as u16, status.y));
+            frame.render_widget(Paragraph::new(line), status);
+        }
+        Mode::Priority => frame.render_widget(
+            Paragraph::new("Priority: 1 urgent, 2 very high, 3 high, 4 normal"),
+            status,
+        ),
+        Mode::Move { targets, selected } => {
+            let area = centered(main);
+            frame.render_widget(Clear, area);
+            frame.render_stateful_widget(
+                List::new(
+                    targets
+                        .iter()
+                        .map(|(title, _)| ListItem::new(title.as_str())),
+                )
+                .block(pane("Move to", true))
+                .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+                area,
+                &mut ListState::default().with_selected(Some(*selected)),
+            );
+            frame.render_widget(Paragraph::new("⏎ move  esc cancel"), status);
+        }
+        Mode::Normal => {
+            let status_text = if app.status.is_empty() {
+                HELP
+            } else {
+                app.status.as_str()
+            };
+            frame.render_widget(
+                Paragraph::new(status_text).style(Style::new().fg(Color::DarkGray)),
+                status,
+            );
+        }
+    }
+}
+
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered().title(title);
+    if focused {
+        block.border_style(Style::new().fg(Color::Cyan))
+    } else {
+        block
+    }
+}
+
+/// Returns an area in the middle of the given one for popups.
+fn centered(area: Rect) -> Rect {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Percentage(20),
+        Constraint::Percentage(60),
+        Constraint::Percentage(20),
+    ])
+    .areas(area);
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Percentage(25),
+        Constraint::Percentage(50),
+        Constraint::Percentage(25),
+    ])
+    .areas(area);
+    area
+}
@@ -0,0 +1,57 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn preview() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("add")
+        .arg("Write report #one/three @two p1 tomorrow 9am")
+        .arg("--preview")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "would create task: new p1 Write report tomorrow 9am @two [Project One/Section Three]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn preview_subtasks() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("add")
+        .arg("Trip #one")
+        .arg("--sub")
+        .arg("Pack @one")
+        .arg("--sub")
+        .arg("Book hotel p2")
+        .arg("--preview")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "would create task: new p4 Trip [Project One]\n\
+             would create task:   ⌞ new p4 Pack @one [Project One]\n\
+             would create task:   ⌞ new p2 Book hotel [Project One]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
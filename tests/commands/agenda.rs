@@ -0,0 +1,39 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn agenda() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("agenda")
+        .arg("--days")
+        .arg("3")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Overdue\n\
+             7000001 p4 Bla bla [REPEAT] every day @two [Project One/Section Three]\n\
+             \n\
+             Today\n\
+             7000002 p4 Test [REPEAT] every day @two [Project One/Section Three]\n\
+             7000010 p4 Big crash [REPEAT] every other day [Project One]\n\
+             \n\
+             Tomorrow\n\
+             7000004 p3 Unheard of [REPEAT] 2022-08-27 08:00:00 EEST [Project One]\n\
+             7000015 p3 Shake it [REPEAT] 2022-08-27 22:00:00 EEST @two [Project One]\n\
+             7000017 p4 Write it down [REPEAT] every day @two [Project One]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn close_by_name() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_close(&cmd, "7000015", 1).await;
+    mocks::mock_task(&cmd, "7000015", 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("close")
+        .arg("shake")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("closed task").and(predicate::str::contains("7000015")));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_unclear_name() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("close")
+        .arg("check it")
+        .arg("-o")
+        .arg("json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no clear match for `check it`"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_unknown_name() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("close")
+        .arg("buy mlik")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no task matching `buy mlik`"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_by_number() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 2).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 2).await;
+    mocks::mock_sections(&cmd, 2).await;
+    mocks::mock_close(&cmd, "7000015", 1).await;
+    mocks::mock_task(&cmd, "7000015", 1).await;
+
+    cmd.cmd()?
+        .arg("list")
+        .arg("-n")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            " 1 7000004 p3 Unheard of [REPEAT] 2022-08-27 08:00:00 EEST [Project One]\n \
+              2 7000015 p3 Shake it",
+        ));
+    cmd.cmd()?
+        .arg("close")
+        .arg("2")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("7000015"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn close_all() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_close(&cmd, "7000006", 1).await;
+    mocks::mock_close(&cmd, "7000008", 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("close")
+        .arg("--all")
+        .arg("--yes")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "closed 7000006 p4 Darn [Project Three/Section Five]\n\
+             closed 7000008 p4 Nope [Project Three/Section Five]\n\
+             closed 2 of 2 tasks\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_all_failures() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_update(&cmd, "7000006", "due_datetime", 1).await;
+    mocks::mock_close(&cmd, "7000006", 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("close")
+        .arg("--all")
+        .arg("--yes")
+        .arg("--complete")
+        .assert()
+        .failure()
+        .stdout(
+            predicate::str::contains("completed 7000006 p4 Darn")
+                .and(predicate::str::contains(
+                    "7000008 p4 Nope [Project Three/Section Five]: unable to complete task",
+                ))
+                .and(predicate::str::contains("completed 1 of 2 tasks")),
+        )
+        .stderr(predicate::str::contains("1 of 2 tasks failed"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn script() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("completions")
+        .arg("bash")
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("complete -F _doist doist")
+                .and(predicate::str::contains("printf '%q'")),
+        );
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("completions")
+        .arg("fish")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""$(commandline -ct)""#));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_projects() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_projects(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["__complete", "--", "doist", "add", "Task", "-P", "proj"])
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "Project One\nProject Two\nProject Three\nProject Four\nProject Five\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn complete_subcommands() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let mut command = cmd.cmd()?;
+    command
+        .args(["__complete", "--", "doist", "projects", "v"])
+        .assert()
+        .success()
+        .stdout(predicate::eq("view\tView details of a single project\n"));
+
+    Ok(())
+}
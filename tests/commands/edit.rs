@@ -0,0 +1,93 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn edit_all() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 2).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_update(&cmd, "7000008", r#""labels":["two"],"priority":4"#, 1).await;
+    mocks::mock_update(&cmd, "7000006", r#""labels":["two"],"priority":4"#, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("edit")
+        .arg("--all")
+        .arg("--yes")
+        .arg("--add-label")
+        .arg("tw")
+        .arg("-p")
+        .arg("1")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "updated 7000006 p4 Darn [Project Three/Section Five]\n\
+             updated 7000008 p4 Nope [Project Three/Section Five]\n\
+             updated 2 of 2 tasks\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_all_failures() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+    mocks::mock_update(&cmd, "7000006", "tomorrow", 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("edit")
+        .arg("--all")
+        .arg("--yes")
+        .arg("--due")
+        .arg("tomorrow")
+        .arg("-o")
+        .arg("json")
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            r#""error": "unable to update task: Bad response from API: 404 Not Found"#,
+        ))
+        .stderr(predicate::str::contains("1 of 2 tasks failed"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn edit_all_requires_confirmation() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("edit")
+        .arg("--all")
+        .arg("-p")
+        .arg("2")
+        .arg("-o")
+        .arg("json")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "use --yes to update tasks without confirmation",
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn list() -> Result<()> {
+    for test in &[
+        vec!["filters"],
+        vec!["filters", "list"],
+        vec!["filter", "l"],
+    ] {
+        let cmd = Tool::init().await?;
+
+        mocks::mock_filters(&cmd, 1).await;
+
+        let mut command = cmd.cmd()?;
+        for arg in test {
+            command.arg(arg);
+        }
+        command.assert().success().stdout(predicate::eq(
+            "Focus (today | overdue)\nLater 7 days & !today\n",
+        ));
+        cmd.mock.verify().await;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn list_saved() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_filters(&cmd, 1).await;
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .arg("--saved")
+        .arg("focus")
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn check() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("filter")
+        .arg("check")
+        .arg("(#Projet One | ##Project*) & /Section Five & @tw & shared")
+        .assert()
+        .failure()
+        .stdout(predicate::eq(
+            "\
+(#Projet One | ##Project*) & /Section Five & @tw & shared
+                                                   ^^^^^^ `shared` is left to the Todoist API and can't be used with --local-filter
+(#Projet One | ##Project*) & /Section Five & @tw & shared
+ ^^^^^^^^^^^ unknown project `Projet One`, did you mean `Project One`?
+(#Projet One | ##Project*) & /Section Five & @tw & shared
+                                             ^^^ unknown label `tw`, did you mean `two`?
+",
+        ))
+        .stderr(predicate::str::contains("filter has 2 problems"));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn check_syntax() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("filter")
+        .arg("check")
+        .arg("today & | overdue")
+        .assert()
+        .failure()
+        .stderr(
+            predicate::str::contains("today & | overdue")
+                .and(predicate::str::contains("^ expected a filter before `|`")),
+        );
+
+    Ok(())
+}
@@ -1,9 +1,13 @@
+pub const COMMENTS: &str = include_str!("./comments.json");
 pub const FETCH_TIME: &str = include_str!("./fetch_time");
+pub const FILTERS: &str = include_str!("./filters.json");
 pub const LABELS: &str = include_str!("./labels.json");
 pub const PROJECTS: &str = include_str!("./projects.json");
 pub const SECTIONS: &str = include_str!("./sections.json");
+pub const TASK: &str = include_str!("./task.json");
 pub const TASKS: &str = include_str!("./tasks.json");
 pub const TASKS_PARTIAL: &str = include_str!("./tasks_partial.json");
+pub const TASKS_COMPLETED: &str = include_str!("./tasks_completed.json");
 pub const TASK_OUTPUT: &str = include_str!("./task_output");
 pub const TASK_EXPAND_OUTPUT: &str = include_str!("./task_expand_output");
 pub const PROJECTS_OUTPUT: &str = include_str!("./projects_output");
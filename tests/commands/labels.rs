@@ -29,3 +29,30 @@ async fn list() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn jsonl() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_labels(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    let output = command
+        .arg("labels")
+        .arg("-o")
+        .arg("jsonl")
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let labels = stdout
+        .lines()
+        .map(serde_json::from_str::<serde_json::Value>)
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(labels.len(), 2);
+    assert_eq!(labels[0]["name"], "one");
+    assert_eq!(labels[1]["id"], "1999992");
+
+    Ok(())
+}
@@ -53,3 +53,273 @@ async fn expand() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    let output = command.arg("list").arg("--output=json").assert().success();
+    cmd.mock.verify().await;
+
+    let tasks: Vec<serde_json::Value> = serde_json::from_slice(&output.get_output().stdout)?;
+    assert_eq!(tasks.len(), 27);
+    assert_eq!(tasks[0]["id"], "7000004");
+    assert_eq!(tasks[0]["project_name"], "Project One");
+    assert_eq!(tasks[0]["section_name"], serde_json::Value::Null);
+    assert_eq!(tasks[4]["project_name"], "Project Three");
+    assert_eq!(tasks[4]["section_name"], "Section Five");
+    assert_eq!(tasks[5]["parent_id"], "7000005");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn csv() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    let output = command
+        .arg("list")
+        .arg("--output=csv")
+        .arg("--columns=id,project,section,labels,priority,due")
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone())?;
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 28);
+    assert_eq!(lines[0], "id,project,section,labels,priority,due");
+    assert_eq!(
+        lines[1],
+        "7000004,Project One,,,p3,2022-08-27T05:00:00+00:00"
+    );
+    assert_eq!(
+        lines[8],
+        "7000002,Project One,Section Three,two,p4,2022-08-26"
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn csv_only_lists() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("labels")
+        .arg("--output=tsv")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "only supported when listing tasks",
+        ));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn format() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_tasks_partial(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("-e")
+        .arg("--nointeractive")
+        .arg("--format={content:8}|{section:>14}|{project:.9}")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "1 Impossible|  Section Five|Project …\n2   ⌞ Nope    |  Section Five|Project …\n3 Darn    |  Section Five|Project …\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn group_by() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .arg("--group-by")
+        .arg("priority")
+        .arg("--sort")
+        .arg("content")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "p3 (2)\n \
+              1 7000015 p3 Shake it [REPEAT] 2022-08-27 22:00:00 EEST @two [Project One]\n \
+              2 7000004 p3 Unheard of [REPEAT] 2022-08-27 08:00:00 EEST [Project One]\n\
+             \n\
+             p4 (25)\n \
+              3 7000010 p4 Big crash [REPEAT] every other day [Project One]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn local_filter() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .arg("--local-filter")
+        .arg("--filter")
+        .arg("##Project One & !no date & (overdue | @two)")
+        .arg("--format")
+        .arg("{content}")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "1 Shake it\n2 Test\n3 Bla bla\n4 Write it down\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn multiple_queries() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .arg("--local-filter")
+        .arg("--filter")
+        .arg("##Project One & overdue, @two")
+        .arg("--format")
+        .arg("{content}")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "##Project One & overdue (1)\n1 Bla bla\n\n@two (4)\n2 Shake it\n3 Test\n4 Bla bla\n5 Write it down\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn multiple_queries_json() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    let output = command
+        .arg("list")
+        .arg("--output=json")
+        .arg("--filter")
+        .arg("(today | overdue), all")
+        .assert()
+        .success();
+    cmd.mock.verify().await;
+
+    let groups: Vec<serde_json::Value> = serde_json::from_slice(&output.get_output().stdout)?;
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0]["group"], "(today | overdue)");
+    assert_eq!(groups[1]["group"], "all");
+    assert_eq!(groups[0]["tasks"].as_array().unwrap().len(), 27);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn ambiguous_project() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .arg("--project")
+        .arg("project")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "no clear match for `project`, candidates are: `Project Five`, `Project Four`, \
+             `Project One`, `Project Three`, `Project Two`",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn invalid_default_format() -> Result<()> {
+    let mut cmd = Tool::init().await?;
+    cmd.cfg.default_format = Some("{nope}".to_string());
+    cmd.cfg.save()?;
+
+    mocks::mock_tasks(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("list")
+        .arg("--nointeractive")
+        .assert()
+        .success()
+        .stdout(predicate::eq(super::fixtures::TASK_OUTPUT))
+        .stderr(predicate::str::starts_with(
+            "warning: ignoring default_format: invalid format '{nope}'",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
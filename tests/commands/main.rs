@@ -1,7 +1,14 @@
+mod add;
+mod agenda;
 mod auth;
+mod close;
+mod completions;
+mod edit;
+mod filter;
 mod fixtures;
 mod labels;
 mod list;
 mod mocks;
 mod projects;
+mod search;
 mod setup;
@@ -41,6 +41,81 @@ pub async fn mock_tasks_partial(tool: &Tool, times: u64) {
     .await
 }
 
+pub async fn mock_task(tool: &Tool, id: &str, times: u64) {
+    mock_http(
+        tool,
+        "GET",
+        &format!("/api/v1/tasks/{id}"),
+        200,
+        super::fixtures::TASK,
+        times,
+    )
+    .await
+}
+
+pub async fn mock_close(tool: &Tool, id: &str, times: u64) {
+    mock_http(
+        tool,
+        "POST",
+        &format!("/api/v1/tasks/{id}/close"),
+        204,
+        "",
+        times,
+    )
+    .await
+}
+
+pub async fn mock_update(tool: &Tool, id: &str, body: &str, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        &format!("/api/v1/tasks/{id}"),
+        204,
+        "",
+        times,
+        |mb| mb.and(matchers::body_string_contains(body)),
+    )
+    .await
+}
+
+pub async fn mock_tasks_completed(tool: &Tool, times: u64) {
+    mock_http(
+        tool,
+        "GET",
+        "/api/v1/tasks/completed/by_completion_date",
+        200,
+        super::fixtures::TASKS_COMPLETED,
+        times,
+    )
+    .await
+}
+
+pub async fn mock_comments(tool: &Tool, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/api/v1/sync",
+        200,
+        super::fixtures::COMMENTS,
+        times,
+        |mb| mb.and(matchers::body_string_contains("notes")),
+    )
+    .await
+}
+
+pub async fn mock_filters(tool: &Tool, times: u64) {
+    mock_http_with_builder(
+        tool,
+        "POST",
+        "/api/v1/sync",
+        200,
+        super::fixtures::FILTERS,
+        times,
+        |mb| mb.and(matchers::body_string_contains("resource_types")),
+    )
+    .await
+}
+
 pub async fn mock_labels(tool: &Tool, times: u64) {
     mock_http(
         tool,
@@ -0,0 +1,61 @@
+use super::mocks;
+use super::setup::Tool;
+use assert_cmd::prelude::*;
+use color_eyre::Result;
+use predicates::prelude::*;
+
+#[tokio::test]
+async fn search() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("search")
+        .arg("data")
+        .arg("-n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "7000016 p4 Get some data 20 Sep [Project Four/Section One]\n\
+             7000027 p4 Open data 13 Sep [Inbox]\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn search_comments_and_completed() -> Result<()> {
+    let cmd = Tool::init().await?;
+
+    mocks::mock_tasks_all(&cmd, 1).await;
+    mocks::mock_tasks_completed(&cmd, 1).await;
+    mocks::mock_comments(&cmd, 1).await;
+    mocks::mock_labels(&cmd, 1).await;
+    mocks::mock_projects(&cmd, 1).await;
+    mocks::mock_sections(&cmd, 1).await;
+
+    let mut command = cmd.cmd()?;
+    command
+        .arg("search")
+        .arg("data")
+        .arg("--comments")
+        .arg("--completed")
+        .arg("-n")
+        .assert()
+        .success()
+        .stdout(predicate::eq(
+            "7000016 p4 Get some data 20 Sep [Project Four/Section One]\n\
+             7000100 p4 Back up data [Inbox] (completed)\n\
+             7000027 p4 Open data 13 Sep [Inbox]\n\
+             7000023 p4 Coffee 15 Sep [Inbox] comment: Grab the data sheet\n",
+        ));
+    cmd.mock.verify().await;
+
+    Ok(())
+}